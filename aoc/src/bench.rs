@@ -0,0 +1,363 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use crate::cli::BenchArgs;
+use crate::solutions::{self, SolveError};
+
+/// Median timings of one part of one day over one input file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Measure {
+    pub day: u8,
+    pub part: u8,
+    /// File name of the input, the key used to match a baseline.
+    pub input: String,
+    pub parse_ns: u64,
+    pub solve_ns: u64,
+}
+
+/// What `--save` writes and `--baseline` reads back.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Baseline {
+    pub runs: u32,
+    pub measures: Vec<Measure>,
+}
+
+/// A phase that got slower than the baseline allows.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Regression<'a> {
+    pub measure: &'a Measure,
+    pub phase: &'static str,
+    pub before_ns: u64,
+    pub after_ns: u64,
+}
+
+impl fmt::Display for Regression<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "day {} part {} {}: {} {:.2?} -> {:.2?}",
+            self.measure.day,
+            self.measure.part,
+            self.measure.input,
+            self.phase,
+            Duration::from_nanos(self.before_ns),
+            Duration::from_nanos(self.after_ns),
+        )
+    }
+}
+
+#[derive(Debug)]
+pub enum BenchError {
+    Io(PathBuf, io::Error),
+    Json(PathBuf, serde_json::Error),
+    Solve {
+        day: u8,
+        part: u8,
+        input: String,
+        err: SolveError,
+    },
+}
+
+impl fmt::Display for BenchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "{}: {err}", path.display()),
+            Self::Json(path, err) => write!(f, "{}: {err}", path.display()),
+            Self::Solve {
+                day,
+                part,
+                input,
+                err,
+            } => write!(f, "day {day} part {part} {input}: {err}"),
+        }
+    }
+}
+
+impl std::error::Error for BenchError {}
+
+/// Input files of `day` in `dir`: `dayN.txt` and any `dayN-<name>.txt`, sorted by name.
+fn input_files(dir: &Path, day: u8) -> Result<Vec<PathBuf>, BenchError> {
+    let exact = format!("day{day}.txt");
+    let prefix = format!("day{day}-");
+    let entries = fs::read_dir(dir).map_err(|err| BenchError::Io(dir.to_path_buf(), err))?;
+
+    let mut files = vec![];
+    for entry in entries {
+        let entry = entry.map_err(|err| BenchError::Io(dir.to_path_buf(), err))?;
+        let name = entry.file_name();
+        let Some(name) = name.to_str() else {
+            continue;
+        };
+        let is_txt = Path::new(name)
+            .extension()
+            .is_some_and(|extension| extension == "txt");
+        if name == exact || (name.starts_with(&prefix) && is_txt) {
+            files.push(entry.path());
+        }
+    }
+    files.sort();
+
+    Ok(files)
+}
+
+fn median(mut durations: Vec<Duration>) -> Duration {
+    durations.sort_unstable();
+    durations
+        .get(durations.len() / 2)
+        .copied()
+        .unwrap_or_default()
+}
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+fn measure(
+    day: u8,
+    part: u8,
+    name: &str,
+    input: &str,
+    runs: u32,
+) -> Option<Result<Measure, SolveError>> {
+    let mut parse = vec![];
+    let mut solve = vec![];
+    for _ in 0..runs {
+        match solutions::time(day, part, input)? {
+            Ok(timing) => {
+                parse.push(timing.parse);
+                solve.push(timing.solve);
+            }
+            Err(err) => return Some(Err(err)),
+        }
+    }
+
+    Some(Ok(Measure {
+        day,
+        part,
+        input: name.to_string(),
+        parse_ns: nanos(median(parse)),
+        solve_ns: nanos(median(solve)),
+    }))
+}
+
+/// Measures every requested day and part over the input files found in `args.inputs`.
+///
+/// # Errors
+///
+/// Returns a [`BenchError`] when an input cannot be read or solved.
+pub fn measure_all(args: &BenchArgs) -> Result<Baseline, BenchError> {
+    let mut measures = vec![];
+    for &day in &args.days {
+        for path in input_files(&args.inputs, day)? {
+            let input =
+                fs::read_to_string(&path).map_err(|err| BenchError::Io(path.clone(), err))?;
+            let name = path
+                .file_name()
+                .map_or_else(String::new, |name| name.to_string_lossy().into_owned());
+            for &part in &args.parts {
+                match measure(day, part, &name, &input, args.runs) {
+                    Some(Ok(measure)) => measures.push(measure),
+                    Some(Err(err)) => {
+                        return Err(BenchError::Solve {
+                            day,
+                            part,
+                            input: name,
+                            err,
+                        });
+                    }
+                    None => {}
+                }
+            }
+        }
+    }
+
+    Ok(Baseline {
+        runs: args.runs,
+        measures,
+    })
+}
+
+fn is_slower(before_ns: u64, after_ns: u64, threshold: u32) -> bool {
+    u128::from(after_ns) * 100 > u128::from(before_ns) * (100 + u128::from(threshold))
+}
+
+/// Phases of `current` more than `threshold` percent slower than the same day,
+/// part and input in `baseline`. Measures missing from the baseline are skipped.
+#[must_use]
+pub fn find_regressions<'a>(
+    baseline: &Baseline,
+    current: &'a Baseline,
+    threshold: u32,
+) -> Vec<Regression<'a>> {
+    let mut regressions = vec![];
+    for measure in &current.measures {
+        let Some(before) = baseline.measures.iter().find(|before| {
+            before.day == measure.day
+                && before.part == measure.part
+                && before.input == measure.input
+        }) else {
+            continue;
+        };
+        for (phase, before_ns, after_ns) in [
+            ("parse", before.parse_ns, measure.parse_ns),
+            ("solve", before.solve_ns, measure.solve_ns),
+        ] {
+            if is_slower(before_ns, after_ns, threshold) {
+                regressions.push(Regression {
+                    measure,
+                    phase,
+                    before_ns,
+                    after_ns,
+                });
+            }
+        }
+    }
+
+    regressions
+}
+
+/// Reads a baseline written by [`save`].
+///
+/// # Errors
+///
+/// Returns a [`BenchError`] when the file cannot be read or is not a baseline.
+pub fn load(path: &Path) -> Result<Baseline, BenchError> {
+    let json = fs::read_to_string(path).map_err(|err| BenchError::Io(path.to_path_buf(), err))?;
+    serde_json::from_str(&json).map_err(|err| BenchError::Json(path.to_path_buf(), err))
+}
+
+/// Writes `baseline` as pretty printed JSON.
+///
+/// # Errors
+///
+/// Returns a [`BenchError`] when the file cannot be written.
+pub fn save(path: &Path, baseline: &Baseline) -> Result<(), BenchError> {
+    let json = serde_json::to_string_pretty(baseline)
+        .map_err(|err| BenchError::Json(path.to_path_buf(), err))?;
+    fs::write(path, json + "\n").map_err(|err| BenchError::Io(path.to_path_buf(), err))
+}
+
+fn bench(args: &BenchArgs) -> Result<ExitCode, BenchError> {
+    let baseline = args.baseline.as_deref().map(load).transpose()?;
+    let current = measure_all(args)?;
+    for measure in &current.measures {
+        println!(
+            "day {} part {} {}: parse {:.2?}, solve {:.2?}",
+            measure.day,
+            measure.part,
+            measure.input,
+            Duration::from_nanos(measure.parse_ns),
+            Duration::from_nanos(measure.solve_ns),
+        );
+    }
+    if let Some(path) = &args.save {
+        save(path, &current)?;
+    }
+
+    let Some(baseline) = baseline else {
+        return Ok(ExitCode::SUCCESS);
+    };
+    let regressions = find_regressions(&baseline, &current, args.threshold);
+    for regression in &regressions {
+        println!("regression: {regression}");
+    }
+    if regressions.is_empty() {
+        println!("no regression above {}%", args.threshold);
+        Ok(ExitCode::SUCCESS)
+    } else {
+        println!(
+            "{} regression(s) above {}%",
+            regressions.len(),
+            args.threshold
+        );
+        Ok(ExitCode::FAILURE)
+    }
+}
+
+pub fn run(args: &BenchArgs) -> ExitCode {
+    bench(args).unwrap_or_else(|err| {
+        eprintln!("aoc: bench: {err}");
+        ExitCode::FAILURE
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample(input: &str, parse_ns: u64, solve_ns: u64) -> Measure {
+        Measure {
+            day: 1,
+            part: 1,
+            input: input.to_string(),
+            parse_ns,
+            solve_ns,
+        }
+    }
+
+    #[test]
+    fn median_durations() {
+        let result = median(vec![
+            Duration::from_nanos(30),
+            Duration::from_nanos(10),
+            Duration::from_nanos(20),
+        ]);
+        assert_eq!(result, Duration::from_nanos(20));
+        let result = median(vec![]);
+        assert_eq!(result, Duration::ZERO);
+    }
+
+    #[test]
+    fn regressions_above_threshold() {
+        let baseline = Baseline {
+            runs: 10,
+            measures: vec![
+                sample("day1.txt", 100, 1000),
+                sample("day1-big.txt", 100, 100),
+            ],
+        };
+        let current = Baseline {
+            runs: 10,
+            measures: vec![
+                sample("day1.txt", 110, 1200),
+                sample("day1-big.txt", 50, 100),
+                sample("day1-new.txt", 100_000, 100_000),
+            ],
+        };
+        let result = find_regressions(&baseline, &current, 10);
+        assert_eq!(
+            result,
+            vec![Regression {
+                measure: &current.measures[0],
+                phase: "solve",
+                before_ns: 1000,
+                after_ns: 1200,
+            }]
+        );
+        let result = find_regressions(&baseline, &current, 25);
+        assert_eq!(result, vec![]);
+    }
+
+    #[test]
+    fn baseline_json() -> Result<(), serde_json::Error> {
+        let baseline = Baseline {
+            runs: 3,
+            measures: vec![sample("day1.txt", 12, 34)],
+        };
+        let json = serde_json::to_string(&baseline)?;
+        assert_eq!(
+            json,
+            r#"{"runs":3,"measures":[{"day":1,"part":1,"input":"day1.txt","parse_ns":12,"solve_ns":34}]}"#
+        );
+        let result: Baseline = serde_json::from_str(&json)?;
+        assert_eq!(result, baseline);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,280 @@
+use std::fmt;
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::solutions::DAYS;
+
+pub const USAGE: &str = "Usage:
+    aoc run --day <DAY> [--part <PART>] [--input <PATH>] [--format <FORMAT>]
+    aoc run --all [--part <PART>] [--inputs <DIR>] [--format <FORMAT>]
+    aoc bench [--day <DAY>] [--part <PART>] [--inputs <DIR>] [--runs <N>]
+              [--baseline <PATH>] [--save <PATH>] [--threshold <PERCENT>]
+    aoc help
+
+Options:
+    --day <DAY>      day to solve (1-11)
+    --all            solve every day in sequence
+    --part <PART>    only solve the given part (1 or 2), both by default
+    --input <PATH>   puzzle input of the selected day, stdin by default
+    --inputs <DIR>   directory holding one `dayN.txt` per day, `inputs` by default
+    --format <FORMAT>
+                     `text` by default, `json` for one JSON object per answer or
+                     `tsv` for a header line then one row per answer, both with
+                     the day, part, answer, parse and solve nanoseconds and the
+                     SHA-256 of the input
+
+Bench options:
+    --day <DAY>            only bench the given day, every day by default
+    --inputs <DIR>         directory holding `dayN.txt` and `dayN-<name>.txt` inputs
+    --runs <N>             runs per input, the median is kept, 10 by default
+    --baseline <PATH>      JSON baseline to flag regressions against
+    --save <PATH>          write the measures as a JSON baseline
+    --threshold <PERCENT>  slowdown tolerated before flagging, 10 by default
+";
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Command {
+    Run(RunArgs),
+    Bench(BenchArgs),
+    Help,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub enum Days {
+    One { day: u8, input: Option<PathBuf> },
+    All { inputs: PathBuf },
+}
+
+/// How `aoc run` prints the answers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Text,
+    Json,
+    Tsv,
+}
+
+impl FromStr for Format {
+    type Err = ();
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "text" => Ok(Self::Text),
+            "json" => Ok(Self::Json),
+            "tsv" => Ok(Self::Tsv),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RunArgs {
+    pub days: Days,
+    pub parts: Vec<u8>,
+    pub format: Format,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct BenchArgs {
+    pub days: Vec<u8>,
+    pub parts: Vec<u8>,
+    pub inputs: PathBuf,
+    pub runs: u32,
+    pub baseline: Option<PathBuf>,
+    pub save: Option<PathBuf>,
+    pub threshold: u32,
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct ArgsError(String);
+
+impl fmt::Display for ArgsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl std::error::Error for ArgsError {}
+
+fn value_of(flag: &str, args: &mut impl Iterator<Item = String>) -> Result<String, ArgsError> {
+    args.next()
+        .ok_or_else(|| ArgsError(format!("missing value for `{flag}`")))
+}
+
+fn parse_number(flag: &str, value: &str, range: &[u8]) -> Result<u8, ArgsError> {
+    match value.parse::<u8>() {
+        Ok(number) if range.contains(&number) => Ok(number),
+        _ => Err(ArgsError(format!("invalid value `{value}` for `{flag}`"))),
+    }
+}
+
+fn parse_value<T: FromStr>(flag: &str, value: &str) -> Result<T, ArgsError> {
+    value
+        .parse()
+        .map_err(|_| ArgsError(format!("invalid value `{value}` for `{flag}`")))
+}
+
+fn parse_run(mut args: impl Iterator<Item = String>) -> Result<RunArgs, ArgsError> {
+    let days: Vec<u8> = DAYS.collect();
+    let mut day = None;
+    let mut all = false;
+    let mut part = None;
+    let mut input = None;
+    let mut inputs = None;
+    let mut format = Format::Text;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(&arg, &value_of(&arg, &mut args)?, &days)?),
+            "--all" => all = true,
+            "--format" => format = parse_value(&arg, &value_of(&arg, &mut args)?)?,
+            "--part" => part = Some(parse_number(&arg, &value_of(&arg, &mut args)?, &[1, 2])?),
+            "--input" => input = Some(PathBuf::from(value_of(&arg, &mut args)?)),
+            "--inputs" => inputs = Some(PathBuf::from(value_of(&arg, &mut args)?)),
+            _ => return Err(ArgsError(format!("unexpected argument `{arg}`"))),
+        }
+    }
+
+    let days = match (day, all) {
+        (Some(_), true) => return Err(ArgsError("`--day` conflicts with `--all`".to_string())),
+        (None, false) => return Err(ArgsError("expected `--day` or `--all`".to_string())),
+        (Some(day), false) => {
+            if inputs.is_some() {
+                return Err(ArgsError("`--inputs` requires `--all`".to_string()));
+            }
+            Days::One { day, input }
+        }
+        (None, true) => {
+            if input.is_some() {
+                return Err(ArgsError("`--input` requires `--day`".to_string()));
+            }
+            Days::All {
+                inputs: inputs.unwrap_or_else(|| PathBuf::from("inputs")),
+            }
+        }
+    };
+    let parts = part.map_or_else(|| vec![1, 2], |part| vec![part]);
+
+    Ok(RunArgs {
+        days,
+        parts,
+        format,
+    })
+}
+
+fn parse_bench(mut args: impl Iterator<Item = String>) -> Result<BenchArgs, ArgsError> {
+    let days: Vec<u8> = DAYS.collect();
+    let mut day = None;
+    let mut part = None;
+    let mut inputs = None;
+    let mut runs = 10;
+    let mut baseline = None;
+    let mut save = None;
+    let mut threshold = 10;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--day" => day = Some(parse_number(&arg, &value_of(&arg, &mut args)?, &days)?),
+            "--part" => part = Some(parse_number(&arg, &value_of(&arg, &mut args)?, &[1, 2])?),
+            "--inputs" => inputs = Some(PathBuf::from(value_of(&arg, &mut args)?)),
+            "--runs" => runs = parse_value(&arg, &value_of(&arg, &mut args)?)?,
+            "--baseline" => baseline = Some(PathBuf::from(value_of(&arg, &mut args)?)),
+            "--save" => save = Some(PathBuf::from(value_of(&arg, &mut args)?)),
+            "--threshold" => threshold = parse_value(&arg, &value_of(&arg, &mut args)?)?,
+            _ => return Err(ArgsError(format!("unexpected argument `{arg}`"))),
+        }
+    }
+    if runs == 0 {
+        return Err(ArgsError("`--runs` must be at least 1".to_string()));
+    }
+
+    Ok(BenchArgs {
+        days: day.map_or(days, |day| vec![day]),
+        parts: part.map_or_else(|| vec![1, 2], |part| vec![part]),
+        inputs: inputs.unwrap_or_else(|| PathBuf::from("inputs")),
+        runs,
+        baseline,
+        save,
+        threshold,
+    })
+}
+
+pub fn parse_args(mut args: impl Iterator<Item = String>) -> Result<Command, ArgsError> {
+    match args.next().as_deref() {
+        Some("run") => Ok(Command::Run(parse_run(args)?)),
+        Some("bench") => Ok(Command::Bench(parse_bench(args)?)),
+        Some("help" | "--help" | "-h") => Ok(Command::Help),
+        Some(command) => Err(ArgsError(format!("unknown command `{command}`"))),
+        None => Err(ArgsError("missing command".to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &str) -> Result<Command, ArgsError> {
+        parse_args(args.split_whitespace().map(str::to_string))
+    }
+
+    #[test]
+    fn parse_run_day() {
+        let result = parse("run --day 8 --part 1 --input day8.txt");
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunArgs {
+                days: Days::One {
+                    day: 8,
+                    input: Some(PathBuf::from("day8.txt")),
+                },
+                parts: vec![1],
+                format: Format::Text,
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_run_all() {
+        let result = parse("run --all --format tsv");
+        assert_eq!(
+            result,
+            Ok(Command::Run(RunArgs {
+                days: Days::All {
+                    inputs: PathBuf::from("inputs"),
+                },
+                parts: vec![1, 2],
+                format: Format::Tsv,
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_bench() {
+        let result = parse("bench --day 3 --runs 5 --baseline base.json --threshold 20");
+        assert_eq!(
+            result,
+            Ok(Command::Bench(BenchArgs {
+                days: vec![3],
+                parts: vec![1, 2],
+                inputs: PathBuf::from("inputs"),
+                runs: 5,
+                baseline: Some(PathBuf::from("base.json")),
+                save: None,
+                threshold: 20,
+            }))
+        );
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(parse("").is_err());
+        assert!(parse("run").is_err());
+        assert!(parse("run --day 12").is_err());
+        assert!(parse("run --day 1 --part 3").is_err());
+        assert!(parse("run --day 1 --all").is_err());
+        assert!(parse("run --all --input day1.txt").is_err());
+        assert!(parse("run --day").is_err());
+        assert!(parse("run --all --format csv").is_err());
+        assert!(parse("bench --runs 0").is_err());
+        assert!(parse("bench --threshold ten").is_err());
+    }
+}
@@ -0,0 +1,101 @@
+mod bench;
+mod cli;
+mod report;
+mod solutions;
+
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process::ExitCode;
+
+use cli::{Command, Days, Format, RunArgs, USAGE, parse_args};
+use report::Report;
+use solutions::SolveError;
+
+fn read_input(path: Option<&Path>) -> io::Result<String> {
+    if let Some(path) = path {
+        return fs::read_to_string(path);
+    }
+    let mut buf = String::new();
+    io::stdin().read_to_string(&mut buf)?;
+
+    Ok(buf)
+}
+
+fn run_day(day: u8, parts: &[u8], input: &str, format: Format) -> ExitCode {
+    let mut status = ExitCode::SUCCESS;
+    let input_hash = report::input_hash(input);
+    for &part in parts {
+        match solutions::time(day, part, input) {
+            Some(Ok(timing)) => match Report::new(day, timing, &input_hash).line(format) {
+                Ok(line) => println!("{line}"),
+                Err(err) => {
+                    eprintln!("aoc: day {day} part {part}: {err}");
+                    status = ExitCode::FAILURE;
+                }
+            },
+            Some(Err(err)) => {
+                eprintln!("aoc: day {day} part {part}: {err}");
+                status = ExitCode::FAILURE;
+                // The other parts would not parse the input either.
+                if matches!(err, SolveError::Parse(_)) {
+                    break;
+                }
+            }
+            None => {}
+        }
+    }
+
+    status
+}
+
+fn run(args: &RunArgs) -> ExitCode {
+    let mut status = ExitCode::SUCCESS;
+    if let Some(header) = report::header(args.format) {
+        println!("{header}");
+    }
+    match &args.days {
+        Days::One { day, input } => match read_input(input.as_deref()) {
+            Ok(input) => status = run_day(*day, &args.parts, &input, args.format),
+            Err(err) => {
+                eprintln!("aoc: day {day}: {err}");
+                status = ExitCode::FAILURE;
+            }
+        },
+        Days::All { inputs } => {
+            for day in solutions::DAYS {
+                let path = inputs.join(format!("day{day}.txt"));
+                match read_input(Some(&path)) {
+                    Ok(input) => {
+                        if run_day(day, &args.parts, &input, args.format) == ExitCode::FAILURE {
+                            status = ExitCode::FAILURE;
+                        }
+                    }
+                    Err(err) => {
+                        eprintln!("aoc: day {day}: {}: {err}", path.display());
+                        status = ExitCode::FAILURE;
+                    }
+                }
+            }
+        }
+    }
+
+    status
+}
+
+fn main() -> ExitCode {
+    match parse_args(env::args().skip(1)) {
+        Ok(Command::Run(args)) => run(&args),
+        Ok(Command::Bench(args)) => bench::run(&args),
+        Ok(Command::Help) => {
+            print!("{USAGE}");
+            ExitCode::SUCCESS
+        }
+        Err(err) => {
+            eprintln!("aoc: {err}");
+            eprint!("{USAGE}");
+            ExitCode::FAILURE
+        }
+    }
+}
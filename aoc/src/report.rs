@@ -0,0 +1,129 @@
+use std::fmt::Write;
+use std::time::Duration;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::cli::Format;
+use crate::solutions::Timing;
+
+/// One solved part, as printed by `aoc run`.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Report {
+    pub day: u8,
+    pub part: u8,
+    pub answer: String,
+    pub parse_ns: u64,
+    pub solve_ns: u64,
+    /// Hex SHA-256 of the puzzle input, to tell apart the answers of different inputs.
+    pub input_hash: String,
+}
+
+const TSV_HEADER: &str = "day\tpart\tanswer\tparse_ns\tsolve_ns\tinput_hash";
+
+fn nanos(duration: Duration) -> u64 {
+    u64::try_from(duration.as_nanos()).unwrap_or(u64::MAX)
+}
+
+#[must_use]
+pub fn input_hash(input: &str) -> String {
+    let mut hash = String::with_capacity(64);
+    for byte in Sha256::digest(input.as_bytes()) {
+        let _ = write!(hash, "{byte:02x}");
+    }
+    hash
+}
+
+impl Report {
+    #[must_use]
+    pub fn new(day: u8, timing: Timing, input_hash: &str) -> Self {
+        Self {
+            day,
+            part: timing.part,
+            answer: timing.answer,
+            parse_ns: nanos(timing.parse),
+            solve_ns: nanos(timing.solve),
+            input_hash: input_hash.to_string(),
+        }
+    }
+
+    /// The report as one line of `format`, without the trailing newline.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`serde_json::Error`] of a report that does not serialize.
+    pub fn line(&self, format: Format) -> Result<String, serde_json::Error> {
+        Ok(match format {
+            Format::Text => format!(
+                "day {} part {}: {} ({:.2?})",
+                self.day,
+                self.part,
+                self.answer,
+                Duration::from_nanos(self.parse_ns.saturating_add(self.solve_ns)),
+            ),
+            Format::Json => serde_json::to_string(self)?,
+            Format::Tsv => format!(
+                "{}\t{}\t{}\t{}\t{}\t{}",
+                self.day, self.part, self.answer, self.parse_ns, self.solve_ns, self.input_hash
+            ),
+        })
+    }
+}
+
+/// Line printed once before the reports, the column names in TSV.
+#[must_use]
+pub const fn header(format: Format) -> Option<&'static str> {
+    match format {
+        Format::Tsv => Some(TSV_HEADER),
+        Format::Text | Format::Json => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Report {
+        Report {
+            day: 3,
+            part: 2,
+            answer: "357".to_string(),
+            parse_ns: 1200,
+            solve_ns: 3400,
+            input_hash: input_hash("abc"),
+        }
+    }
+
+    #[test]
+    fn sha256_input_hash() {
+        let result = input_hash("abc");
+        assert_eq!(
+            result,
+            "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+        );
+    }
+
+    #[test]
+    fn report_lines() -> Result<(), serde_json::Error> {
+        let report = sample();
+        assert_eq!(report.line(Format::Text)?, "day 3 part 2: 357 (4.60µs)");
+        assert_eq!(
+            report.line(Format::Json)?,
+            format!(
+                r#"{{"day":3,"part":2,"answer":"357","parse_ns":1200,"solve_ns":3400,"input_hash":"{}"}}"#,
+                report.input_hash
+            )
+        );
+        assert_eq!(
+            report.line(Format::Tsv)?,
+            format!("3\t2\t357\t1200\t3400\t{}", report.input_hash)
+        );
+        assert_eq!(
+            header(Format::Tsv).map(|header| header.split('\t').count()),
+            Some(6)
+        );
+        assert_eq!(header(Format::Json), None);
+
+        Ok(())
+    }
+}
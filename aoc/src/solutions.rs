@@ -0,0 +1,135 @@
+use std::error::Error;
+use std::fmt;
+use std::hint::black_box;
+use std::ops::RangeInclusive;
+use std::time::{Duration, Instant};
+
+use common::{ParseError, Solution};
+
+pub const DAYS: RangeInclusive<u8> = 1..=11;
+
+#[derive(Debug)]
+pub enum SolveError {
+    /// The input did not parse, so no part of the day can be solved from it.
+    Parse(ParseError),
+    /// The input parsed but the part could not be solved from it.
+    Solve(Box<dyn Error>),
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(err) => write!(f, "{err}"),
+            Self::Solve(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+/// Answer of one part and the time spent parsing the input and solving it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Timing {
+    pub part: u8,
+    pub answer: String,
+    pub parse: Duration,
+    pub solve: Duration,
+}
+
+fn time_day<S: Solution>(part: u8, input: &str) -> Option<Result<Timing, SolveError>> {
+    let start = Instant::now();
+    let parsed = match S::parse(black_box(input)) {
+        Ok(parsed) => parsed,
+        Err(err) => return Some(Err(SolveError::Parse(err))),
+    };
+    let parse = start.elapsed();
+
+    let start = Instant::now();
+    let answer = match part {
+        1 => S::solve_part_1(black_box(&parsed)).map(black_box),
+        2 => S::solve_part_2(black_box(&parsed)).map(black_box),
+        _ => return None,
+    };
+    let solve = start.elapsed();
+
+    Some(
+        answer
+            .map(|answer| Timing {
+                part,
+                answer: answer.to_string(),
+                parse,
+                solve,
+            })
+            .map_err(|err| SolveError::Solve(err.into())),
+    )
+}
+
+/// Parses `input` and solves the given part once, timing both phases apart.
+#[must_use]
+pub fn time(day: u8, part: u8, input: &str) -> Option<Result<Timing, SolveError>> {
+    match day {
+        1 => time_day::<day1::Day1>(part, input),
+        2 => time_day::<day2::Day2>(part, input),
+        3 => time_day::<day3::Day3>(part, input),
+        4 => time_day::<day4::Day4>(part, input),
+        5 => time_day::<day5::Day5>(part, input),
+        6 => time_day::<day6::Day6>(part, input),
+        7 => time_day::<day7::Day7>(part, input),
+        8 => time_day::<day8::Day8>(part, input),
+        9 => time_day::<day9::Day9>(part, input),
+        10 => time_day::<day10::Day10>(part, input),
+        11 => time_day::<day11::Day11>(part, input),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::fs;
+    use std::path::Path;
+
+    use common::parse_answers;
+
+    use super::*;
+
+    /// Runs the recorded inputs of every day through [`time`], so that the
+    /// dispatch parses each day the way its own golden test does.
+    #[test]
+    fn dispatch_recorded_inputs() -> Result<(), Box<dyn Error>> {
+        let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("..");
+        for day in DAYS {
+            let inputs = root.join(format!("day{day}")).join("inputs");
+            let mut checked = 0;
+            for entry in fs::read_dir(&inputs)? {
+                let path = entry?.path();
+                if path.extension().is_none_or(|extension| extension != "txt") {
+                    continue;
+                }
+                let input = fs::read_to_string(&path)?;
+                let answers = fs::read_to_string(path.with_extension("answers"))?;
+                for (part, expected) in parse_answers(&answers)? {
+                    let timing = time(day, part, &input).ok_or("no such part")??;
+                    assert_eq!(timing.answer, expected, "{} part {part}", path.display());
+                    checked += 1;
+                }
+            }
+            assert!(checked > 0, "day {day} has no recorded answer");
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn parse_and_solve_errors() {
+        let result = time(1, 1, "L1\nX2");
+        assert!(matches!(result, Some(Err(SolveError::Parse(_)))));
+        // Only the column layout of the second part rejects this worksheet.
+        let input = "123 328\n 45 64\n *  +  ";
+        let result = time(6, 1, input).map(|timing| timing.map(|timing| timing.answer));
+        assert!(matches!(result, Some(Ok(answer)) if answer == "5927"));
+        let result = time(6, 2, input);
+        assert!(matches!(result, Some(Err(SolveError::Solve(_)))));
+        assert!(time(6, 3, input).is_none());
+    }
+}
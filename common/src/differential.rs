@@ -0,0 +1,126 @@
+use std::fmt::Debug;
+use std::ops::Range;
+
+/// A case on which the reference and the fast implementations disagree, shrunk
+/// as much as possible.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counterexample<T, R> {
+    /// Seed of the generated case before shrinking.
+    pub seed: u64,
+    pub case: Vec<T>,
+    pub reference: R,
+    pub fast: R,
+}
+
+/// Shrinks `case` while `fails` holds: first drops chunks of items, halving the
+/// chunk size down to single items, then replaces each item by the first of its
+/// `simplify` candidates that still fails. Loops until nothing changes, so the
+/// candidates must always be strictly simpler than the item.
+pub fn shrink<T: Clone>(
+    mut case: Vec<T>,
+    fails: impl Fn(&[T]) -> bool,
+    simplify: impl Fn(&T) -> Vec<T>,
+) -> Vec<T> {
+    loop {
+        let mut progress = false;
+
+        let mut chunk = case.len();
+        while chunk > 0 {
+            let mut start = 0;
+            while start < case.len() {
+                let end = (start + chunk).min(case.len());
+                let candidate: Vec<T> = case[..start].iter().chain(&case[end..]).cloned().collect();
+                if fails(&candidate) {
+                    case = candidate;
+                    progress = true;
+                } else {
+                    start += chunk;
+                }
+            }
+            chunk /= 2;
+        }
+
+        for index in 0..case.len() {
+            for simpler in simplify(&case[index]) {
+                let mut candidate = case.clone();
+                candidate[index] = simpler;
+                if fails(&candidate) {
+                    case = candidate;
+                    progress = true;
+                    break;
+                }
+            }
+        }
+
+        if !progress {
+            return case;
+        }
+    }
+}
+
+/// Feeds the case generated from every seed to both implementations and shrinks
+/// the first case they disagree on.
+///
+/// # Errors
+///
+/// Returns the shrunk [`Counterexample`] when the implementations disagree.
+pub fn differential<T, R>(
+    seeds: Range<u64>,
+    generate: impl Fn(u64) -> Vec<T>,
+    simplify: impl Fn(&T) -> Vec<T>,
+    reference: impl Fn(&[T]) -> R,
+    fast: impl Fn(&[T]) -> R,
+) -> Result<(), Counterexample<T, R>>
+where
+    T: Clone + Debug,
+    R: PartialEq + Debug,
+{
+    let fails = |case: &[T]| reference(case) != fast(case);
+    for seed in seeds {
+        let case = generate(seed);
+        if fails(&case) {
+            let case = shrink(case, fails, &simplify);
+            return Err(Counterexample {
+                seed,
+                reference: reference(&case),
+                fast: fast(&case),
+                case,
+            });
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn shrink_to_minimal() {
+        let fails = |case: &[u64]| case.contains(&7) && case.iter().sum::<u64>() > 10;
+        let simplify = |item: &u64| if *item > 0 { vec![0, item / 2] } else { vec![] };
+        let result = shrink(vec![1, 9, 7, 3, 8, 2], fails, simplify);
+        assert_eq!(result, vec![4, 7]);
+    }
+
+    #[test]
+    fn differential_counterexample() {
+        let result = differential(
+            0..10,
+            |seed| (0..=seed).collect(),
+            |_| vec![],
+            |case: &[u64]| case.iter().sum::<u64>(),
+            |case: &[u64]| case.iter().filter(|&&item| item != 5).sum::<u64>(),
+        );
+        assert_eq!(
+            result,
+            Err(Counterexample {
+                seed: 5,
+                case: vec![5],
+                reference: 5,
+                fast: 0,
+            })
+        );
+    }
+}
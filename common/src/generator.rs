@@ -0,0 +1,108 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::{ParseError, Solution};
+
+/// Seeds [`check_generator`] runs the generator with.
+const SEEDS: Range<u64> = 0..8;
+
+/// A generated input that cannot be used as a puzzle input.
+pub enum GeneratorError {
+    /// The seed generated two different inputs.
+    Unstable(u64),
+    /// The input generated from the seed does not parse.
+    Parse(u64, ParseError),
+}
+
+impl fmt::Display for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Unstable(seed) => write!(f, "seed {seed}: generated two different inputs"),
+            Self::Parse(seed, err) => write!(f, "seed {seed}: {err}"),
+        }
+    }
+}
+
+// Tests print the `Debug` of the error they return, keep it readable.
+impl fmt::Debug for GeneratorError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for GeneratorError {}
+
+/// Runs `generate` on a few seeds, checking that a seed always generates the
+/// same input and that the input parses as a puzzle input of `S`. Returns the
+/// parsed inputs, for the day to check what its options promise.
+///
+/// # Errors
+///
+/// Returns a [`GeneratorError`] on the first seed generating two different
+/// inputs or an input that does not parse.
+pub fn check_generator<S: Solution>(
+    generate: impl Fn(u64) -> String,
+) -> Result<Vec<S::Input>, GeneratorError> {
+    let mut inputs = vec![];
+    for seed in SEEDS {
+        let input = generate(seed);
+        if input != generate(seed) {
+            return Err(GeneratorError::Unstable(seed));
+        }
+        inputs.push(S::parse(&input).map_err(|err| GeneratorError::Parse(seed, err))?);
+    }
+
+    Ok(inputs)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+    use std::convert::Infallible;
+
+    use super::*;
+    use crate::words;
+
+    struct Numbers;
+
+    impl Solution for Numbers {
+        const DAY: u8 = 0;
+
+        type Input = Vec<u64>;
+        type Answer = u64;
+        type Error = Infallible;
+
+        fn parse(input: &str) -> Result<Self::Input, ParseError> {
+            words(input).map(|word| word.parse("number")).collect()
+        }
+
+        fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+            Ok(input.iter().sum())
+        }
+
+        fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+            Ok(input.iter().product())
+        }
+    }
+
+    #[test]
+    fn generator() -> Result<(), GeneratorError> {
+        let result = check_generator::<Numbers>(|seed| format!("{seed} {}", seed * 2))?;
+        assert_eq!(result.len(), SEEDS.count());
+        assert_eq!(result[3], [3, 6]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_generator() {
+        let result = check_generator::<Numbers>(|seed| format!("{seed} x"));
+        assert!(matches!(result, Err(GeneratorError::Parse(0, _))));
+        let calls = Cell::new(0);
+        let result = check_generator::<Numbers>(|_| {
+            calls.set(calls.get() + 1);
+            calls.get().to_string()
+        });
+        assert!(matches!(result, Err(GeneratorError::Unstable(0))));
+    }
+}
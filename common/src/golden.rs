@@ -0,0 +1,192 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::{ParseError, Solution, lines};
+
+/// An answer that differs from the one recorded next to the input.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    pub input: PathBuf,
+    pub part: u8,
+    pub expected: String,
+    pub found: String,
+}
+
+impl fmt::Display for Mismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} part {}: expected `{}`, found `{}`",
+            self.input.display(),
+            self.part,
+            self.expected,
+            self.found
+        )
+    }
+}
+
+pub enum GoldenError {
+    Io(PathBuf, io::Error),
+    /// An input or its answers file does not parse.
+    Parse(PathBuf, ParseError),
+    /// An input without a `.answers` file next to it.
+    MissingAnswers(PathBuf),
+    /// The directory does not hold any input.
+    NoInputs(PathBuf),
+    Mismatches(Vec<Mismatch>),
+}
+
+impl fmt::Display for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "{}: {err}", path.display()),
+            Self::Parse(path, err) => write!(f, "{}: {err}", path.display()),
+            Self::MissingAnswers(path) => write!(f, "{}: no recorded answers", path.display()),
+            Self::NoInputs(path) => write!(f, "{}: no `.txt` input", path.display()),
+            Self::Mismatches(mismatches) => {
+                write!(f, "{} answer(s) differ", mismatches.len())?;
+                for mismatch in mismatches {
+                    write!(f, "\n    {mismatch}")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+// Tests print the `Debug` of the error they return, keep it readable.
+impl fmt::Debug for GoldenError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl std::error::Error for GoldenError {}
+
+/// Parses an answers file: one `part N: answer` line per recorded part.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on the first line that does not record an answer.
+pub fn parse_answers(input: &str) -> Result<Vec<(u8, String)>, ParseError> {
+    let mut answers = vec![];
+    for line in lines(input).filter(|line| !line.text.trim().is_empty()) {
+        let Some((part, answer)) = line.split_once(':') else {
+            return Err(line.error("answer `part N: answer`"));
+        };
+        let part = part.trim();
+        let part = match part.text {
+            "part 1" => 1,
+            "part 2" => 2,
+            _ => return Err(part.error("`part 1` or `part 2`")),
+        };
+        answers.push((part, answer.trim().text.to_string()));
+    }
+
+    Ok(answers)
+}
+
+/// Inputs of `dir`, the `.txt` files, sorted by name.
+fn inputs(dir: &Path) -> Result<Vec<PathBuf>, GoldenError> {
+    let entries = fs::read_dir(dir).map_err(|err| GoldenError::Io(dir.to_path_buf(), err))?;
+    let mut inputs = vec![];
+    for entry in entries {
+        let path = entry
+            .map_err(|err| GoldenError::Io(dir.to_path_buf(), err))?
+            .path();
+        if path.extension().is_some_and(|extension| extension == "txt") {
+            inputs.push(path);
+        }
+    }
+    inputs.sort();
+
+    Ok(inputs)
+}
+
+fn read(path: &Path) -> Result<String, GoldenError> {
+    fs::read_to_string(path).map_err(|err| GoldenError::Io(path.to_path_buf(), err))
+}
+
+/// Solves every `.txt` input of `dir` and compares the answers with the ones
+/// recorded in the `.answers` file of the same name. Parts missing from the
+/// answers file are not checked. Returns the number of answers checked.
+///
+/// # Errors
+///
+/// Returns a [`GoldenError`] when an input cannot be read or parsed, has no
+/// answers file, or when some answers differ from the recorded ones.
+pub fn check_inputs<S: Solution>(dir: &Path) -> Result<usize, GoldenError> {
+    let inputs = inputs(dir)?;
+    if inputs.is_empty() {
+        return Err(GoldenError::NoInputs(dir.to_path_buf()));
+    }
+
+    let mut checked = 0;
+    let mut mismatches = vec![];
+    for input in inputs {
+        let answers_path = input.with_extension("answers");
+        if !answers_path.exists() {
+            return Err(GoldenError::MissingAnswers(input));
+        }
+        let answers = parse_answers(&read(&answers_path)?)
+            .map_err(|err| GoldenError::Parse(answers_path, err))?;
+        let parsed =
+            S::parse(&read(&input)?).map_err(|err| GoldenError::Parse(input.clone(), err))?;
+
+        for (part, expected) in answers {
+            let found = match part {
+                1 => S::solve_part_1(&parsed),
+                _ => S::solve_part_2(&parsed),
+            };
+            let found = match found {
+                Ok(answer) => answer.to_string(),
+                Err(err) => format!("error: {err}"),
+            };
+            if found != expected {
+                mismatches.push(Mismatch {
+                    input: input.clone(),
+                    part,
+                    expected,
+                    found,
+                });
+            }
+            checked += 1;
+        }
+    }
+
+    if mismatches.is_empty() {
+        Ok(checked)
+    } else {
+        Err(GoldenError::Mismatches(mismatches))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn answers() -> Result<(), ParseError> {
+        let input = "part 1: 3\n\npart 2:  6 \n";
+        let result = parse_answers(input)?;
+        assert_eq!(result, vec![(1, "3".to_string()), (2, "6".to_string())]);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_answers() {
+        let result = parse_answers("part 1: 3\n6\n");
+        assert_eq!(
+            result,
+            Err(ParseError::new(2, 1, "answer `part N: answer`", "6"))
+        );
+        let result = parse_answers("part 3: 3\n");
+        assert_eq!(
+            result,
+            Err(ParseError::new(1, 1, "`part 1` or `part 2`", "part 3"))
+        );
+    }
+}
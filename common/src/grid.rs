@@ -0,0 +1,308 @@
+use std::fmt::{self, Display};
+use std::ops::{Index, IndexMut};
+
+use crate::{ParseError, Token, lines};
+
+/// Position of a cell: `x` counts the columns from the left, `y` the rows from
+/// the top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Point {
+    pub x: usize,
+    pub y: usize,
+}
+
+impl Point {
+    #[must_use]
+    pub const fn new(x: usize, y: usize) -> Self {
+        Self { x, y }
+    }
+}
+
+/// Steps to the 4 cells sharing a side with a cell.
+const SIDES: [(isize, isize); 4] = [(0, -1), (-1, 0), (1, 0), (0, 1)];
+
+/// Steps to the 8 cells touching a cell, corners included, in row-major order.
+const AROUND: [(isize, isize); 8] = [
+    (-1, -1),
+    (0, -1),
+    (1, -1),
+    (-1, 0),
+    (1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+];
+
+/// A rectangle of cells stored row after row.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+impl<T> Grid<T> {
+    /// Cuts `cells` into rows of `width` cells, `None` when the last row would
+    /// be incomplete.
+    #[must_use]
+    pub fn new(width: usize, cells: Vec<T>) -> Option<Self> {
+        let height = match width {
+            0 if cells.is_empty() => 0,
+            0 => return None,
+            width if cells.len().is_multiple_of(width) => cells.len() / width,
+            _ => return None,
+        };
+
+        Some(Self {
+            width,
+            height,
+            cells,
+        })
+    }
+
+    /// Parses one row per line, one cell per character, with `cell` turning a
+    /// character and its token into a cell. Blank lines are skipped.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`ParseError`] of the first character `cell` rejects, or one
+    /// on the first row that is not as wide as the first one.
+    pub fn parse(
+        input: &str,
+        cell: impl Fn(char, Token) -> Result<T, ParseError>,
+    ) -> Result<Self, ParseError> {
+        let mut width = None;
+        let mut cells = vec![];
+        for row in lines(input).filter(|line| !line.text.trim().is_empty()) {
+            let start = cells.len();
+            for (c, token) in row.chars() {
+                cells.push(cell(c, token)?);
+            }
+            let len = cells.len() - start;
+            match width {
+                None => width = Some(len),
+                Some(width) if width != len => {
+                    return Err(row.error(format!("row of {width} cells")));
+                }
+                Some(_) => {}
+            }
+        }
+
+        let width = width.unwrap_or_default();
+        Ok(Self {
+            width,
+            height: cells.len().checked_div(width).unwrap_or_default(),
+            cells,
+        })
+    }
+
+    /// Same as [`Grid::parse`] but never fails: `cell` keeps every character
+    /// somehow and the rows shorter than the widest one are padded with `fill`.
+    pub fn parse_lenient(input: &str, cell: impl Fn(char) -> T, fill: T) -> Self
+    where
+        T: Clone,
+    {
+        let rows: Vec<Vec<T>> = input
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .map(|row| row.chars().map(&cell).collect())
+            .collect();
+        let width = rows.iter().map(Vec::len).max().unwrap_or_default();
+        let mut cells = Vec::with_capacity(width * rows.len());
+        for mut row in rows {
+            row.resize(width, fill.clone());
+            cells.append(&mut row);
+        }
+
+        Self {
+            width,
+            height: cells.len().checked_div(width).unwrap_or_default(),
+            cells,
+        }
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> usize {
+        self.width
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> usize {
+        self.height
+    }
+
+    #[must_use]
+    pub const fn contains(&self, point: Point) -> bool {
+        point.x < self.width && point.y < self.height
+    }
+
+    #[must_use]
+    pub fn get(&self, point: Point) -> Option<&T> {
+        self.contains(point)
+            .then(|| &self.cells[point.y * self.width + point.x])
+    }
+
+    #[must_use]
+    pub fn get_mut(&mut self, point: Point) -> Option<&mut T> {
+        self.contains(point)
+            .then(|| &mut self.cells[point.y * self.width + point.x])
+    }
+
+    /// Every position of the grid, row after row. Does not borrow the grid, so
+    /// that the cells can be changed along the way.
+    pub fn points(&self) -> impl Iterator<Item = Point> + use<T> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| Point { x, y }))
+    }
+
+    /// Every cell of the grid along with its position, row after row.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.points().zip(&self.cells)
+    }
+
+    /// Grid of the same size with `cell` applied to every cell and its position.
+    #[must_use]
+    pub fn map<U>(&self, mut cell: impl FnMut(Point, &T) -> U) -> Grid<U> {
+        Grid {
+            width: self.width,
+            height: self.height,
+            cells: self
+                .iter()
+                .map(|(point, value)| cell(point, value))
+                .collect(),
+        }
+    }
+
+    /// The rows of the grid, from the top.
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        self.cells.chunks(self.width.max(1))
+    }
+
+    /// Position `dx` columns and `dy` rows away from `point`, `None` when it is
+    /// out of the grid.
+    #[must_use]
+    pub fn offset(&self, point: Point, dx: isize, dy: isize) -> Option<Point> {
+        let point = Point {
+            x: point.x.checked_add_signed(dx)?,
+            y: point.y.checked_add_signed(dy)?,
+        };
+        self.contains(point).then_some(point)
+    }
+
+    /// Positions in the grid sharing a side with `point`.
+    pub fn neighbors4(&self, point: Point) -> impl Iterator<Item = Point> {
+        SIDES
+            .iter()
+            .filter_map(move |&(dx, dy)| self.offset(point, dx, dy))
+    }
+
+    /// Positions in the grid touching `point`, corners included.
+    pub fn neighbors8(&self, point: Point) -> impl Iterator<Item = Point> {
+        AROUND
+            .iter()
+            .filter_map(move |&(dx, dy)| self.offset(point, dx, dy))
+    }
+}
+
+/// # Panics
+///
+/// Panics when `point` is out of the grid.
+impl<T> Index<Point> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, point: Point) -> &T {
+        assert!(self.contains(point), "{point:?} is out of the grid");
+        &self.cells[point.y * self.width + point.x]
+    }
+}
+
+/// # Panics
+///
+/// Panics when `point` is out of the grid.
+impl<T> IndexMut<Point> for Grid<T> {
+    fn index_mut(&mut self, point: Point) -> &mut T {
+        assert!(self.contains(point), "{point:?} is out of the grid");
+        &mut self.cells[point.y * self.width + point.x]
+    }
+}
+
+/// Writes the cells row after row, one line per row, the way [`Grid::parse`]
+/// reads them back.
+impl<T: Display> Display for Grid<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (y, row) in self.rows().enumerate() {
+            if y > 0 {
+                writeln!(f)?;
+            }
+            for cell in row {
+                write!(f, "{cell}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse_cell(c: char, token: Token) -> Result<char, ParseError> {
+        match c {
+            '#' | '.' => Ok(c),
+            _ => Err(token.error("`#` or `.`")),
+        }
+    }
+
+    #[test]
+    fn parse_grid() -> Result<(), ParseError> {
+        let input = "#..\n.#.\n\n..#\n";
+        let grid = Grid::parse(input, parse_cell)?;
+        assert_eq!((grid.width(), grid.height()), (3, 3));
+        assert_eq!(grid[Point::new(2, 2)], '#');
+        assert_eq!(grid.get(Point::new(3, 0)), None);
+        assert_eq!(grid.to_string(), "#..\n.#.\n..#");
+        assert_eq!(Grid::parse(&grid.to_string(), parse_cell)?, grid);
+
+        let result = Grid::parse("#..\n.#\n..#", parse_cell);
+        assert_eq!(result, Err(ParseError::new(2, 1, "row of 3 cells", ".#")));
+        let result = Grid::parse("#..\n.x.", parse_cell);
+        assert_eq!(result, Err(ParseError::new(2, 2, "`#` or `.`", "x")));
+        let result = Grid::parse("#. .#\n.....", parse_cell);
+        assert_eq!(result, Err(ParseError::new(1, 3, "`#` or `.`", " ")));
+        let grid = Grid::parse("", parse_cell)?;
+        assert_eq!((grid.width(), grid.height()), (0, 0));
+        assert_eq!(grid.to_string(), "");
+
+        Ok(())
+    }
+
+    #[test]
+    fn lenient_grid() {
+        let grid = Grid::parse_lenient("#.\n.#x\n#", |c| c, ' ');
+        assert_eq!(grid.to_string(), "#. \n.#x\n#  ");
+        let grid = Grid::parse_lenient("#. .#\n\n..", |c| c, '?');
+        assert_eq!(grid.to_string(), "#. .#\n..???");
+        assert_eq!(Grid::new(2, vec![1, 2, 3]), None);
+        assert_eq!(
+            Grid::new(2, vec![1, 2, 3, 4]).map(|grid| grid.height()),
+            Some(2)
+        );
+    }
+
+    #[test]
+    fn grid_neighbors() {
+        let grid = Grid::parse_lenient("...\n...\n...", |c| c, ' ');
+        let corner: Vec<Point> = grid.neighbors4(Point::new(0, 0)).collect();
+        assert_eq!(corner, vec![Point::new(1, 0), Point::new(0, 1)]);
+        assert_eq!(grid.neighbors8(Point::new(0, 0)).count(), 3);
+        assert_eq!(grid.neighbors8(Point::new(2, 1)).count(), 5);
+        assert_eq!(grid.neighbors8(Point::new(1, 1)).count(), 8);
+        assert_eq!(grid.neighbors4(Point::new(1, 1)).count(), 4);
+        assert_eq!(grid.offset(Point::new(2, 2), 1, 0), None);
+        let points: Vec<Point> = grid.points().skip(2).take(2).collect();
+        assert_eq!(points, vec![Point::new(2, 0), Point::new(0, 1)]);
+        let counts = grid.map(|point, _| grid.neighbors8(point).count());
+        assert_eq!(counts.to_string(), "353\n585\n353");
+    }
+}
@@ -0,0 +1,219 @@
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt::{self, Display};
+use std::fs;
+use std::io::{self, BufRead, BufReader, Read};
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+/// Where a binary reads one puzzle input from: a file, or stdin for `-`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Source {
+    Stdin,
+    File(PathBuf),
+}
+
+impl Source {
+    /// Reads the whole input.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`io::Error`] of the failed read, including invalid UTF-8.
+    pub fn read(&self) -> io::Result<String> {
+        match self {
+            Self::Stdin => {
+                let mut buf = String::new();
+                io::stdin().read_to_string(&mut buf)?;
+                Ok(buf)
+            }
+            Self::File(path) => fs::read_to_string(path),
+        }
+    }
+
+    /// Opens the input to read it bit by bit instead of all at once.
+    ///
+    /// # Errors
+    ///
+    /// Returns the [`io::Error`] of a file that cannot be opened.
+    pub fn open(&self) -> io::Result<Box<dyn BufRead>> {
+        match self {
+            Self::Stdin => Ok(Box::new(io::stdin().lock())),
+            Self::File(path) => Ok(Box::new(BufReader::new(fs::File::open(path)?))),
+        }
+    }
+}
+
+impl From<OsString> for Source {
+    fn from(arg: OsString) -> Self {
+        if arg == "-" {
+            Self::Stdin
+        } else {
+            Self::File(arg.into())
+        }
+    }
+}
+
+impl Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Stdin => write!(f, "<stdin>"),
+            Self::File(path) => write!(f, "{}", path.display()),
+        }
+    }
+}
+
+/// Sources named by the command line arguments, stdin alone when there is none.
+#[must_use]
+pub fn sources(args: impl Iterator<Item = OsString>) -> Vec<Source> {
+    let sources: Vec<Source> = args.map(Source::from).collect();
+    if sources.is_empty() {
+        vec![Source::Stdin]
+    } else {
+        sources
+    }
+}
+
+/// Removes every `flag` from the command line arguments, telling whether there
+/// was one.
+#[must_use]
+pub fn take_flag(args: &mut Vec<OsString>, flag: &str) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != flag);
+    args.len() != len
+}
+
+/// Reads and solves every source, printing one answer per line. When there are
+/// several sources each answer is prefixed with its source.
+fn solve_sources<A: Display>(
+    sources: &[Source],
+    solve: impl Fn(&Source) -> Result<A, Box<dyn Error>>,
+    out: &mut impl io::Write,
+    err: &mut impl io::Write,
+) -> ExitCode {
+    let mut status = ExitCode::SUCCESS;
+    for source in sources {
+        let answer = solve(source);
+        let written = match answer {
+            Ok(answer) if sources.len() == 1 => writeln!(out, "{answer}"),
+            Ok(answer) => writeln!(out, "{source}: {answer}"),
+            Err(error) => {
+                status = ExitCode::FAILURE;
+                writeln!(err, "{source}: {error}")
+            }
+        };
+        if written.is_err() {
+            return ExitCode::FAILURE;
+        }
+    }
+
+    status
+}
+
+/// Body of the `partN` binaries: solves each input named on the command line,
+/// `-` or no argument at all for stdin. An input that cannot be read or solved
+/// is reported on stderr and the next one is still solved.
+pub fn solve_inputs<A: Display>(solve: impl Fn(&str) -> Result<A, Box<dyn Error>>) -> ExitCode {
+    solve_args(std::env::args_os().skip(1), solve)
+}
+
+/// Same as [`solve_inputs`] with `args` in place of the command line arguments,
+/// for the binaries taking flags besides the inputs.
+pub fn solve_args<A: Display>(
+    args: impl IntoIterator<Item = OsString>,
+    solve: impl Fn(&str) -> Result<A, Box<dyn Error>>,
+) -> ExitCode {
+    let sources = sources(args.into_iter());
+    solve_sources(
+        &sources,
+        |source| solve(&source.read()?),
+        &mut io::stdout(),
+        &mut io::stderr(),
+    )
+}
+
+/// Same as [`solve_inputs`] for the binaries streaming their input instead of
+/// reading it all at once.
+pub fn solve_readers<A: Display>(
+    solve: impl Fn(&mut dyn BufRead) -> Result<A, Box<dyn Error>>,
+) -> ExitCode {
+    solve_reader_args(std::env::args_os().skip(1), solve)
+}
+
+/// Same as [`solve_readers`] with `args` in place of the command line
+/// arguments, like [`solve_args`].
+pub fn solve_reader_args<A: Display>(
+    args: impl IntoIterator<Item = OsString>,
+    solve: impl Fn(&mut dyn BufRead) -> Result<A, Box<dyn Error>>,
+) -> ExitCode {
+    let sources = sources(args.into_iter());
+    solve_sources(
+        &sources,
+        |source| solve(&mut source.open()?),
+        &mut io::stdout(),
+        &mut io::stderr(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn count_lines(source: &Source) -> Result<usize, Box<dyn Error>> {
+        let input = source.read()?;
+        if input.is_empty() {
+            return Err("empty input".into());
+        }
+        Ok(input.lines().count())
+    }
+
+    #[test]
+    fn command_line_sources() {
+        let result = sources(std::iter::empty());
+        assert_eq!(result, vec![Source::Stdin]);
+        let result = sources(["a.txt", "-"].into_iter().map(OsString::from));
+        assert_eq!(
+            result,
+            vec![Source::File(PathBuf::from("a.txt")), Source::Stdin]
+        );
+        let mut args: Vec<OsString> = ["--csv", "a.txt"].into_iter().map(OsString::from).collect();
+        assert!(take_flag(&mut args, "--csv"));
+        assert!(!take_flag(&mut args, "--csv"));
+        assert_eq!(args, vec![OsString::from("a.txt")]);
+    }
+
+    #[test]
+    fn one_answer_per_source() {
+        let dir = std::env::temp_dir().join(format!("common-input-{}", std::process::id()));
+        let _ = fs::create_dir_all(&dir);
+        let three = dir.join("three.txt");
+        let empty = dir.join("empty.txt");
+        let _ = fs::write(&three, "a\nb\nc\n");
+        let _ = fs::write(&empty, "");
+        let missing = dir.join("missing.txt");
+        let sources = [
+            Source::File(three.clone()),
+            Source::File(missing.clone()),
+            Source::File(empty.clone()),
+        ];
+
+        let mut out = vec![];
+        let mut err = vec![];
+        let result = solve_sources(&sources, count_lines, &mut out, &mut err);
+        let mut alone = vec![];
+        let alone_result = solve_sources(&sources[..1], count_lines, &mut alone, &mut err);
+        let _ = fs::remove_dir_all(&dir);
+
+        assert_eq!(alone_result, ExitCode::SUCCESS);
+        assert_eq!(String::from_utf8_lossy(&alone), "3\n");
+        assert_eq!(result, ExitCode::FAILURE);
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            format!("{}: 3\n", three.display())
+        );
+        let err = String::from_utf8_lossy(&err);
+        let lines: Vec<&str> = err.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(&format!("{}: ", missing.display())));
+        assert_eq!(lines[1], format!("{}: empty input", empty.display()));
+    }
+}
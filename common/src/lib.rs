@@ -0,0 +1,49 @@
+mod differential;
+mod generator;
+mod golden;
+mod grid;
+mod input;
+mod parse;
+mod rng;
+mod stream;
+
+use std::error::Error;
+use std::fmt::Display;
+
+pub use differential::{Counterexample, differential, shrink};
+pub use generator::{GeneratorError, check_generator};
+pub use golden::{GoldenError, Mismatch, check_inputs, parse_answers};
+pub use grid::{Grid, Point};
+pub use input::{
+    Source, solve_args, solve_inputs, solve_reader_args, solve_readers, sources, take_flag,
+};
+pub use parse::{ParseError, Token, lines, words};
+pub use rng::Rng;
+pub use stream::StreamError;
+
+/// A puzzle day: the input is parsed once into a typed model that both parts are solved from.
+pub trait Solution {
+    /// Day of the calendar the solution belongs to.
+    const DAY: u8;
+
+    type Input;
+    type Answer: Display;
+    type Error: Error + 'static;
+
+    /// Strictly parses the puzzle input.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] pointing at the first token that does not fit the puzzle format.
+    fn parse(input: &str) -> Result<Self::Input, ParseError>;
+
+    /// # Errors
+    ///
+    /// Returns [`Self::Error`] when the input is valid but cannot be solved.
+    fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error>;
+
+    /// # Errors
+    ///
+    /// Returns [`Self::Error`] when the input is valid but cannot be solved.
+    fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error>;
+}
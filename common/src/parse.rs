@@ -0,0 +1,203 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Error reported by the strict parsers, pointing at the offending token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// 1-based line of the offending token.
+    pub line: usize,
+    /// 1-based column, counted in characters, of the offending token.
+    pub column: usize,
+    /// Description of what the parser was looking for.
+    pub expected: String,
+    /// The text found instead, empty when the input stopped short.
+    pub found: String,
+}
+
+impl ParseError {
+    #[must_use]
+    pub fn new(
+        line: usize,
+        column: usize,
+        expected: impl Into<String>,
+        found: impl Into<String>,
+    ) -> Self {
+        Self {
+            line,
+            column,
+            expected: expected.into(),
+            found: found.into(),
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "line {}, column {}: expected {}, ",
+            self.line, self.column, self.expected
+        )?;
+        if self.found.is_empty() {
+            write!(f, "found nothing")
+        } else {
+            write!(f, "found `{}`", self.found)
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// A slice of the puzzle input that remembers where it starts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+    pub text: &'a str,
+    /// 1-based line of the first character.
+    pub line: usize,
+    /// 1-based column of the first character.
+    pub column: usize,
+}
+
+impl<'a> Token<'a> {
+    #[must_use]
+    pub const fn new(text: &'a str, line: usize, column: usize) -> Self {
+        Self { text, line, column }
+    }
+
+    /// Sub-token covering the bytes `start..end` of this token.
+    #[must_use]
+    pub fn slice(self, start: usize, end: usize) -> Self {
+        Self {
+            text: &self.text[start..end],
+            line: self.line,
+            column: self.column + self.text[..start].chars().count(),
+        }
+    }
+
+    /// Empty token right after the last character, used to report missing input.
+    #[must_use]
+    pub fn end(self) -> Self {
+        self.slice(self.text.len(), self.text.len())
+    }
+
+    #[must_use]
+    pub fn trim(self) -> Self {
+        let start = self.text.len() - self.text.trim_start().len();
+        let end = self.text.trim_end().len().max(start);
+        self.slice(start, end)
+    }
+
+    pub fn split(self, separator: char) -> impl Iterator<Item = Self> {
+        let mut start = 0;
+        self.text
+            .match_indices(separator)
+            .map(|(index, _)| index)
+            .chain(std::iter::once(self.text.len()))
+            .map(move |end| {
+                let token = self.slice(start, end);
+                start = end + separator.len_utf8();
+                token
+            })
+    }
+
+    #[must_use]
+    pub fn split_once(self, separator: char) -> Option<(Self, Self)> {
+        let index = self.text.find(separator)?;
+        Some((
+            self.slice(0, index),
+            self.slice(index + separator.len_utf8(), self.text.len()),
+        ))
+    }
+
+    /// Whitespace separated words of the token.
+    pub fn words(self) -> impl Iterator<Item = Self> {
+        self.split(' ')
+            .flat_map(|token| token.split('\t'))
+            .map(Self::trim)
+            .filter(|token| !token.text.is_empty())
+    }
+
+    /// Characters of the token along with their own position.
+    pub fn chars(self) -> impl Iterator<Item = (char, Self)> {
+        self.text.char_indices().map(move |(index, c)| {
+            let token = self.slice(index, index + c.len_utf8());
+            (c, token)
+        })
+    }
+
+    #[must_use]
+    pub fn error(self, expected: impl Into<String>) -> ParseError {
+        ParseError::new(self.line, self.column, expected, self.text)
+    }
+
+    /// Parses the whole token, reporting `expected` when it is not a valid `T`.
+    ///
+    /// # Errors
+    ///
+    /// Returns a [`ParseError`] located at this token when the text is not a valid `T`.
+    pub fn parse<T: FromStr>(self, expected: &str) -> Result<T, ParseError> {
+        self.text.parse::<T>().map_err(|_| self.error(expected))
+    }
+}
+
+/// Lines of the input, without their line terminator.
+pub fn lines(input: &str) -> impl Iterator<Item = Token<'_>> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(index, line)| Token::new(line, index + 1, 1))
+}
+
+/// Whitespace separated words of the input, line by line.
+pub fn words(input: &str) -> impl Iterator<Item = Token<'_>> {
+    lines(input).flat_map(Token::words)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn words_position() {
+        let input = "ab  cd\n\n  ef";
+        let result: Vec<Token> = words(input).collect();
+        assert_eq!(
+            result,
+            vec![
+                Token::new("ab", 1, 1),
+                Token::new("cd", 1, 5),
+                Token::new("ef", 3, 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn split_position() {
+        let input = Token::new("1,22,,3", 2, 4);
+        let result: Vec<Token> = input.split(',').collect();
+        assert_eq!(
+            result,
+            vec![
+                Token::new("1", 2, 4),
+                Token::new("22", 2, 6),
+                Token::new("", 2, 9),
+                Token::new("3", 2, 10),
+            ]
+        );
+    }
+
+    #[test]
+    fn error_display() {
+        let result = Token::new("L12x", 3, 7).parse::<u64>("distance");
+        assert_eq!(
+            result.map_err(|err| err.to_string()),
+            Err("line 3, column 7: expected distance, found `L12x`".to_string())
+        );
+        let result = Token::new("abc", 1, 1).end().error("digit");
+        assert_eq!(
+            result.to_string(),
+            "line 1, column 4: expected digit, found nothing"
+        );
+    }
+}
@@ -0,0 +1,87 @@
+use std::ops::RangeInclusive;
+
+/// Small deterministic pseudo-random generator (`SplitMix64`) used by the input
+/// generators: the same seed always gives the same puzzle.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    #[must_use]
+    pub const fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub const fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform value of `range`, its start when the range is empty.
+    pub fn range(&mut self, range: RangeInclusive<u64>) -> u64 {
+        let (start, end) = range.into_inner();
+        if end <= start {
+            return start;
+        }
+        match (end - start).checked_add(1) {
+            Some(len) => start + self.next_u64() % len,
+            None => self.next_u64(),
+        }
+    }
+
+    /// Uniform index below `len`, 0 when `len` is 0.
+    pub fn index(&mut self, len: usize) -> usize {
+        let last = u64::try_from(len.saturating_sub(1)).unwrap_or(u64::MAX);
+        usize::try_from(self.range(0..=last)).unwrap_or_default()
+    }
+
+    /// `true` with a probability of `percent` out of 100.
+    pub fn chance(&mut self, percent: u64) -> bool {
+        self.range(0..=99) < percent
+    }
+
+    /// Uniform element of `items`, `None` when it is empty.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> Option<&'a T> {
+        items.get(self.index(items.len()))
+    }
+
+    /// Fisher-Yates shuffle.
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for index in (1..items.len()).rev() {
+            items.swap(index, self.index(index + 1));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_values() {
+        let mut a = Rng::new(42);
+        let mut b = Rng::new(42);
+        let result: Vec<u64> = (0..8).map(|_| a.next_u64()).collect();
+        assert_eq!(result, (0..8).map(|_| b.next_u64()).collect::<Vec<_>>());
+        let mut c = Rng::new(43);
+        assert_ne!(result[0], c.next_u64());
+    }
+
+    #[test]
+    fn range_bounds() {
+        let mut rng = Rng::new(7);
+        for _ in 0..1000 {
+            let result = rng.range(3..=5);
+            assert!((3..=5).contains(&result));
+            let result = rng.index(4);
+            assert!(result < 4);
+        }
+        assert_eq!(rng.range(9..=9), 9);
+        assert_eq!(rng.index(0), 0);
+        assert_eq!(rng.pick::<u8>(&[]), None);
+    }
+}
@@ -0,0 +1,56 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+
+use crate::ParseError;
+
+/// Why a day failed to solve its input while streaming it from a reader, with
+/// `E` the error of its solver.
+#[derive(Debug)]
+pub enum StreamError<E> {
+    Io(io::Error),
+    Parse(ParseError),
+    Solve(E),
+}
+
+impl<E: fmt::Display> fmt::Display for StreamError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(err) => write!(f, "{err}"),
+            Self::Parse(err) => write!(f, "{err}"),
+            Self::Solve(err) => write!(f, "{err}"),
+        }
+    }
+}
+
+impl<E: Error> Error for StreamError<E> {}
+
+impl<E> From<io::Error> for StreamError<E> {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl<E> From<ParseError> for StreamError<E> {
+    fn from(err: ParseError) -> Self {
+        Self::Parse(err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stream_error_display() {
+        let err: StreamError<fmt::Error> = ParseError::new(2, 3, "digit", "x").into();
+        assert_eq!(
+            err.to_string(),
+            "line 2, column 3: expected digit, found `x`"
+        );
+        let err: StreamError<fmt::Error> = io::Error::other("disk failure").into();
+        assert_eq!(err.to_string(), "disk failure");
+        let err = StreamError::Solve(fmt::Error);
+        assert_eq!(err.to_string(), fmt::Error.to_string());
+    }
+}
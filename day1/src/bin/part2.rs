@@ -1,25 +1,18 @@
-use std::io::{self, Read};
+use std::error::Error;
+use std::io::BufRead;
+use std::process::ExitCode;
 
-use day1::solve_part_2;
+use common::solve_readers;
+use day1::{Counting, Dial, try_solve_reader};
 
-fn parse_str(str: &str) -> Vec<String> {
-    let mut parsed_str = vec![];
-
-    for word in str.split_whitespace() {
-        parsed_str.push(word.to_string());
-    }
-    parsed_str
-}
-
-fn get_input() -> Vec<String> {
-    let mut buf = String::new();
-    let _ = io::stdin().read_to_string(&mut buf);
-
-    parse_str(&buf)
+fn solve(reader: &mut dyn BufRead) -> Result<u64, Box<dyn Error>> {
+    Ok(try_solve_reader(
+        reader,
+        &Dial::default(),
+        Counting::PassThrough,
+    )?)
 }
 
-fn main() {
-    let input = get_input();
-    let result = solve_part_2(input.iter().map(std::string::String::as_str).collect());
-    println!("{result}");
+fn main() -> ExitCode {
+    solve_readers(solve)
 }
@@ -0,0 +1,32 @@
+use std::error::Error;
+use std::fmt::Write;
+use std::process::ExitCode;
+
+use common::solve_inputs;
+use day1::{Dial, parse_rotations};
+
+/// One row per rotation: where the dial was before and after it, how many times
+/// it pointed at zero and whether it stopped there.
+fn solve(input: &str) -> Result<String, Box<dyn Error>> {
+    let rotations = parse_rotations(input)?;
+
+    let mut table = format!(
+        "{:>10} {:>6} {:>6} {:>6} {:>6}",
+        "rotation", "before", "after", "passes", "landed"
+    );
+    for step in Dial::default().trace(rotations) {
+        let rotation = step.rotation.to_string();
+        let landed = if step.landed { "yes" } else { "no" };
+        let _ = write!(
+            table,
+            "\n{rotation:>10} {:>6} {:>6} {:>6} {landed:>6}",
+            step.before, step.after, step.passes
+        );
+    }
+
+    Ok(table)
+}
+
+fn main() -> ExitCode {
+    solve_inputs(solve)
+}
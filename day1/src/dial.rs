@@ -0,0 +1,349 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::{Rotation, SolveError};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum DialError {
+    /// A dial needs at least one position.
+    NoPosition,
+    /// The starting position or a target is not on the dial.
+    OutOfDial { position: u64, size: u64 },
+}
+
+impl fmt::Display for DialError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoPosition => write!(f, "a dial needs at least one position"),
+            Self::OutOfDial { position, size } => {
+                write!(f, "position {position} is not on a dial of size {size}")
+            }
+        }
+    }
+}
+
+impl Error for DialError {}
+
+/// What [`Dial::count`] counts.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Counting {
+    /// Rotations leaving the dial on a target, part 1 of the puzzle.
+    LandOn,
+    /// Clicks pointing the dial at a target, while rotating included, part 2.
+    PassThrough,
+}
+
+/// A dial of `size` positions, numbered from 0, rotating from its current
+/// position and watching a set of target positions.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Dial {
+    size: u64,
+    position: u64,
+    targets: Vec<u64>,
+}
+
+/// The dial of the puzzle: 100 positions, starting at 50 and watching 0.
+impl Default for Dial {
+    fn default() -> Self {
+        Self {
+            size: 100,
+            position: 50,
+            targets: vec![0],
+        }
+    }
+}
+
+impl Dial {
+    /// # Errors
+    ///
+    /// Returns a [`DialError`] when `size` is 0 or when `start` or a target is
+    /// not below `size`.
+    pub fn new(
+        size: u64,
+        start: u64,
+        targets: impl IntoIterator<Item = u64>,
+    ) -> Result<Self, DialError> {
+        if size == 0 {
+            return Err(DialError::NoPosition);
+        }
+        let mut targets: Vec<u64> = targets.into_iter().collect();
+        targets.sort_unstable();
+        targets.dedup();
+        if let Some(&position) = targets.iter().chain([&start]).find(|&&p| p >= size) {
+            return Err(DialError::OutOfDial { position, size });
+        }
+
+        Ok(Self {
+            size,
+            position: start,
+            targets,
+        })
+    }
+
+    #[must_use]
+    pub const fn size(&self) -> u64 {
+        self.size
+    }
+
+    #[must_use]
+    pub const fn position(&self) -> u64 {
+        self.position
+    }
+
+    /// Turns the dial straight to `position`, without counting anything.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`DialError::OutOfDial`] when `position` is not below the size.
+    pub const fn set_position(&mut self, position: u64) -> Result<(), DialError> {
+        if position >= self.size {
+            return Err(DialError::OutOfDial {
+                position,
+                size: self.size,
+            });
+        }
+        self.position = position;
+
+        Ok(())
+    }
+
+    /// Target positions, sorted without duplicates.
+    #[must_use]
+    pub fn targets(&self) -> &[u64] {
+        &self.targets
+    }
+
+    #[must_use]
+    pub fn is_on_target(&self) -> bool {
+        self.targets.binary_search(&self.position).is_ok()
+    }
+
+    /// Clicks from `from` to `to` going up, a whole turn when they are equal.
+    const fn clicks_up(&self, from: u64, to: u64) -> u64 {
+        if to > from {
+            to - from
+        } else {
+            self.size - (from - to)
+        }
+    }
+
+    /// Applies `rotation` and returns how many of its clicks pointed the dial
+    /// at a target, the last one included. At most one target per click, so
+    /// the count never exceeds the distance.
+    pub fn rotate(&mut self, rotation: Rotation) -> u64 {
+        let (distance, right) = match rotation {
+            Rotation::Left(distance) => (distance, false),
+            Rotation::Right(distance) => (distance, true),
+        };
+
+        let mut passes = 0;
+        for &target in &self.targets {
+            let first = if right {
+                self.clicks_up(self.position, target)
+            } else {
+                self.clicks_up(target, self.position)
+            };
+            if distance >= first {
+                passes += (distance - first) / self.size + 1;
+            }
+        }
+
+        let turn = distance % self.size;
+        self.position = match (right, turn) {
+            (_, 0) => self.position,
+            (true, turn) => self
+                .position
+                .checked_sub(self.size - turn)
+                .unwrap_or(self.position + turn),
+            (false, turn) => self
+                .position
+                .checked_sub(turn)
+                .unwrap_or(self.position + (self.size - turn)),
+        };
+
+        passes
+    }
+
+    /// Steps of the rotations applied one after the other from the current
+    /// position. The dial itself does not move.
+    pub fn trace<I: IntoIterator<Item = Rotation>>(&self, rotations: I) -> Trace<I::IntoIter> {
+        Trace {
+            dial: self.clone(),
+            rotations: rotations.into_iter(),
+        }
+    }
+
+    /// Counts, from the current position, what `counting` asks for over all the
+    /// rotations. The dial itself does not move.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::Overflow`] when the count does not fit in a `u64`.
+    pub fn count(
+        &self,
+        rotations: impl IntoIterator<Item = Rotation>,
+        counting: Counting,
+    ) -> Result<u64, SolveError> {
+        let mut result: u64 = 0;
+        for step in self.trace(rotations) {
+            let hits = match counting {
+                Counting::LandOn => u64::from(step.landed),
+                Counting::PassThrough => step.passes,
+            };
+            result = result.checked_add(hits).ok_or(SolveError::Overflow)?;
+        }
+
+        Ok(result)
+    }
+}
+
+/// What one rotation did to the dial.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Step {
+    pub rotation: Rotation,
+    pub before: u64,
+    pub after: u64,
+    /// Clicks pointing the dial at a target, the last one included.
+    pub passes: u64,
+    /// Whether the rotation left the dial on a target.
+    pub landed: bool,
+}
+
+/// Iterator over the [`Step`]s of rotations, built by [`Dial::trace`].
+#[derive(Debug, Clone)]
+pub struct Trace<I> {
+    dial: Dial,
+    rotations: I,
+}
+
+impl<I> Trace<I> {
+    /// The dial as left by the steps so far.
+    #[must_use]
+    pub const fn dial(&self) -> &Dial {
+        &self.dial
+    }
+}
+
+impl<I: Iterator<Item = Rotation>> Iterator for Trace<I> {
+    type Item = Step;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let rotation = self.rotations.next()?;
+        let before = self.dial.position;
+        let passes = self.dial.rotate(rotation);
+
+        Some(Step {
+            rotation,
+            before,
+            after: self.dial.position,
+            passes,
+            landed: self.dial.is_on_target(),
+        })
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.rotations.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn invalid_dial() {
+        assert_eq!(Dial::new(0, 0, []), Err(DialError::NoPosition));
+        assert_eq!(
+            Dial::new(10, 10, [0]),
+            Err(DialError::OutOfDial {
+                position: 10,
+                size: 10
+            })
+        );
+        assert_eq!(
+            Dial::new(10, 0, [3, 12]),
+            Err(DialError::OutOfDial {
+                position: 12,
+                size: 10
+            })
+        );
+    }
+
+    #[test]
+    fn rotate_small_dial() -> Result<(), DialError> {
+        let mut dial = Dial::new(5, 1, [0, 3, 3])?;
+        assert_eq!(dial.targets(), &[0, 3]);
+        assert_eq!(dial.rotate(Rotation::Right(2)), 1);
+        assert_eq!(dial.position(), 3);
+        assert!(dial.is_on_target());
+        assert_eq!(dial.rotate(Rotation::Left(11)), 4);
+        assert_eq!(dial.position(), 2);
+        assert_eq!(dial.rotate(Rotation::Right(0)), 0);
+        assert_eq!(dial.rotate(Rotation::Left(5)), 2);
+        assert_eq!(dial.position(), 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn huge_dial() -> Result<(), DialError> {
+        let mut dial = Dial::new(u64::MAX, u64::MAX - 1, [0])?;
+        assert_eq!(dial.rotate(Rotation::Right(u64::MAX)), 1);
+        assert_eq!(dial.position(), u64::MAX - 1);
+        assert_eq!(dial.rotate(Rotation::Right(1)), 1);
+        assert_eq!(dial.position(), 0);
+        assert_eq!(dial.rotate(Rotation::Left(2)), 0);
+        assert_eq!(dial.position(), u64::MAX - 2);
+
+        Ok(())
+    }
+
+    #[test]
+    fn count_modes() -> Result<(), DialError> {
+        let dial = Dial::new(10, 0, [0, 5])?;
+        let rotations = [Rotation::Right(5), Rotation::Right(12), Rotation::Left(7)];
+        assert_eq!(dial.count(rotations, Counting::LandOn), Ok(2));
+        assert_eq!(dial.count(rotations, Counting::PassThrough), Ok(5));
+        assert_eq!(dial.position(), 0);
+
+        Ok(())
+    }
+
+    #[test]
+    fn trace_steps() {
+        let rotations = [Rotation::Left(68), Rotation::Left(30), Rotation::Right(248)];
+        let mut trace = Dial::default().trace(rotations);
+        assert_eq!(
+            trace.next(),
+            Some(Step {
+                rotation: Rotation::Left(68),
+                before: 50,
+                after: 82,
+                passes: 1,
+                landed: false,
+            })
+        );
+        assert_eq!(
+            trace.next(),
+            Some(Step {
+                rotation: Rotation::Left(30),
+                before: 82,
+                after: 52,
+                passes: 0,
+                landed: false,
+            })
+        );
+        assert_eq!(
+            trace.next(),
+            Some(Step {
+                rotation: Rotation::Right(248),
+                before: 52,
+                after: 0,
+                passes: 3,
+                landed: true,
+            })
+        );
+        assert_eq!(trace.next(), None);
+        assert_eq!(trace.dial().position(), 0);
+    }
+}
@@ -0,0 +1,52 @@
+use std::fmt::Write;
+
+use common::Rng;
+
+/// Size knobs of [`generate`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub rotations: usize,
+    /// Largest distance of a rotation, several turns of the dial when above 100.
+    pub max_distance: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            rotations: 4000,
+            max_distance: 1000,
+        }
+    }
+}
+
+/// Generates one rotation per line, such as `L68` or `R1000`.
+#[must_use]
+pub fn generate(seed: u64, options: &Options) -> String {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    for _ in 0..options.rotations {
+        let direction = if rng.chance(50) { 'L' } else { 'R' };
+        let distance = rng.range(1..=options.max_distance);
+        let _ = writeln!(input, "{direction}{distance}");
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{GeneratorError, check_generator};
+
+    use super::*;
+    use crate::Day1;
+
+    #[test]
+    fn generated_input_parses() -> Result<(), GeneratorError> {
+        let options = Options::default();
+        for input in check_generator::<Day1>(|seed| generate(seed, &options))? {
+            assert_eq!(input.len(), options.rotations);
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,191 @@
+use crate::{Counting, Dial, Rotation, SolveError};
+
+/// Distances of the unknown rotation consistent with an observed count, found
+/// by [`Dial::distances_for`].
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Distances {
+    /// Any distance congruent to one of these modulo the size of the dial: full
+    /// turns do not change the count.
+    Periodic(Vec<u64>),
+    /// Exactly these distances, each full turn adding one pass per target.
+    Exact(Vec<u64>),
+}
+
+/// Most positions of a dial the inverse searches try one by one.
+pub const MAX_SEARCH_SIZE: u64 = 1 << 20;
+
+impl Dial {
+    fn check_search_size(&self) -> Result<(), SolveError> {
+        if self.size() > MAX_SEARCH_SIZE {
+            return Err(SolveError::DialTooLarge {
+                size: self.size(),
+                max: MAX_SEARCH_SIZE,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Starting positions from which `rotations` give `count`, in increasing
+    /// order. Tries every position, in O(size × rotations).
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::DialTooLarge`] when the dial has more than
+    /// [`MAX_SEARCH_SIZE`] positions and [`SolveError::Overflow`] when a count
+    /// does not fit in a `u64`.
+    pub fn starts_for(
+        &self,
+        rotations: &[Rotation],
+        counting: Counting,
+        count: u64,
+    ) -> Result<Vec<u64>, SolveError> {
+        self.check_search_size()?;
+        let mut dial = self.clone();
+        let mut starts = vec![];
+        for start in 0..self.size() {
+            // Always below the size, cannot fail.
+            let _ = dial.set_position(start);
+            if dial.count(rotations.iter().copied(), counting)? == count {
+                starts.push(start);
+            }
+        }
+
+        Ok(starts)
+    }
+
+    /// Distances of the rotation at `unknown`, keeping its direction, for which
+    /// `rotations` give `count` from the current position. Tries every distance
+    /// below the size of the dial, in O(size × rotations), the larger ones only
+    /// adding full turns.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::MissingRotation`] when there is no rotation at
+    /// `unknown`, [`SolveError::DialTooLarge`] when the dial has more than
+    /// [`MAX_SEARCH_SIZE`] positions and [`SolveError::Overflow`] when a count
+    /// does not fit in a `u64`.
+    pub fn distances_for(
+        &self,
+        rotations: &[Rotation],
+        unknown: usize,
+        counting: Counting,
+        count: u64,
+    ) -> Result<Distances, SolveError> {
+        let Some(&rotation) = rotations.get(unknown) else {
+            return Err(SolveError::MissingRotation {
+                index: unknown,
+                len: rotations.len(),
+            });
+        };
+        self.check_search_size()?;
+        let with_distance = |distance| match rotation {
+            Rotation::Left(_) => Rotation::Left(distance),
+            Rotation::Right(_) => Rotation::Right(distance),
+        };
+        let turn_passes = match counting {
+            Counting::LandOn => 0,
+            Counting::PassThrough => self.targets().len() as u64,
+        };
+
+        let mut candidate = rotations.to_vec();
+        let mut distances = vec![];
+        for distance in 0..self.size() {
+            candidate[unknown] = with_distance(distance);
+            let found = self.count(candidate.iter().copied(), counting)?;
+            if turn_passes == 0 {
+                if found == count {
+                    distances.push(distance);
+                }
+            } else if let Some(missing) = count.checked_sub(found)
+                && missing.is_multiple_of(turn_passes)
+                && let Some(distance) = (missing / turn_passes)
+                    .checked_mul(self.size())
+                    .and_then(|turns| turns.checked_add(distance))
+            {
+                distances.push(distance);
+            }
+        }
+
+        if turn_passes == 0 {
+            Ok(Distances::Periodic(distances))
+        } else {
+            distances.sort_unstable();
+            Ok(Distances::Exact(distances))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{DialError, parse_rotations};
+
+    const EXAMPLE: &str = "L68 L30 R48 L5 R60 L55 L1 L99 R14 L82";
+
+    #[test]
+    fn example_starts() -> Result<(), Box<dyn std::error::Error>> {
+        let rotations = parse_rotations(EXAMPLE)?;
+        let dial = Dial::default();
+        let result = dial.starts_for(&rotations, Counting::LandOn, 3)?;
+        assert!(result.contains(&50));
+        for start in 0..100 {
+            let mut dial = Dial::new(100, start, [0])?;
+            let count = dial.count(rotations.iter().copied(), Counting::PassThrough)?;
+            dial.set_position(0)?;
+            let result = dial.starts_for(&rotations, Counting::PassThrough, count)?;
+            assert!(result.contains(&start));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn example_distances() -> Result<(), Box<dyn std::error::Error>> {
+        let rotations = parse_rotations(EXAMPLE)?;
+        let dial = Dial::default();
+        let result = dial.distances_for(&rotations, 0, Counting::PassThrough, 6)?;
+        let mut expected = vec![];
+        for distance in 0..2000 {
+            let mut candidate = rotations.clone();
+            candidate[0] = Rotation::Left(distance);
+            if dial.count(candidate, Counting::PassThrough)? == 6 {
+                expected.push(distance);
+            }
+        }
+        assert!(expected.contains(&68));
+        assert_eq!(result, Distances::Exact(expected));
+
+        let Distances::Periodic(result) = dial.distances_for(&rotations, 2, Counting::LandOn, 3)?
+        else {
+            return Err("expected periodic distances".into());
+        };
+        assert!(result.contains(&48));
+        assert!(result.iter().all(|&distance| distance < 100));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_inverse() -> Result<(), DialError> {
+        let dial = Dial::new(10, 0, [])?;
+        let result = dial.distances_for(&[Rotation::Right(3)], 1, Counting::PassThrough, 0);
+        assert_eq!(
+            result,
+            Err(SolveError::MissingRotation { index: 1, len: 1 })
+        );
+        let result = dial.distances_for(&[Rotation::Right(3)], 0, Counting::PassThrough, 0);
+        assert_eq!(result, Ok(Distances::Periodic((0..10).collect())));
+        let dial = Dial::new(u64::MAX, 0, [0])?;
+        let expected = Err(SolveError::DialTooLarge {
+            size: u64::MAX,
+            max: MAX_SEARCH_SIZE,
+        });
+        let result = dial.starts_for(&[Rotation::Right(3)], Counting::LandOn, 1);
+        assert_eq!(result, expected);
+        let result = dial.distances_for(&[Rotation::Right(3)], 0, Counting::LandOn, 1);
+        assert_eq!(result.map(|_| vec![]), expected);
+
+        Ok(())
+    }
+}
@@ -1,178 +1,249 @@
-use std::cmp::Ordering;
+mod dial;
+pub mod generate;
+mod inverse;
+mod lock;
+mod stream;
 
-#[derive(Debug, PartialEq, Copy, Clone)]
-enum Rotation {
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use common::{ParseError, Solution, Token, words};
+
+pub use dial::{Counting, Dial, DialError, Step, Trace};
+pub use inverse::{Distances, MAX_SEARCH_SIZE};
+pub use lock::{Gear, Instruction, Lock, LockError, LockReport, parse_instructions};
+pub use stream::{Rotations, StreamError, read_rotations, try_solve_reader};
+
+/// Rotation of the dial by a number of clicks, written `L68` or `R48`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub enum Rotation {
     Left(u64),
     Right(u64),
 }
 
-fn convert_rotation(rotation: &str) -> Option<Rotation> {
-    match rotation.as_bytes().first() {
-        Some(b'L') => rotation[1..]
-            .parse::<u64>()
-            .map_or(None, |rotate| Some(Rotation::Left(rotate))),
-        Some(b'R') => rotation[1..]
-            .parse::<u64>()
-            .map_or(None, |rotate| Some(Rotation::Right(rotate))),
-        _ => None,
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveError {
+    /// The count of zeros does not fit in a `u64`.
+    Overflow,
+    /// The unknown rotation of an inverse search is past the end of the rotations.
+    MissingRotation { index: usize, len: usize },
+    /// The dial of an inverse search has more positions than it tries.
+    DialTooLarge { size: u64, max: u64 },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "the count of zeros overflows a u64"),
+            Self::MissingRotation { index, len } => {
+                write!(f, "no rotation {index} among {len} rotations")
+            }
+            Self::DialTooLarge { size, max } => {
+                write!(f, "cannot search a dial of {size} positions, at most {max}")
+            }
+        }
     }
 }
 
-#[must_use]
-pub fn solve_part_1(input: Vec<&str>) -> u64 {
-    let mut rotations = vec![];
-    for rotation in input {
-        if let Some(rotation) = convert_rotation(rotation) {
-            rotations.push(rotation);
+impl Error for SolveError {}
+
+/// Parses the digits of a distance, pointing at the first character that is not one.
+fn parse_distance(distance: Token) -> Result<u64, ParseError> {
+    if let Some((_, token)) = distance.chars().find(|(c, _)| !c.is_ascii_digit()) {
+        return Err(token.error("digit of the rotation distance"));
+    }
+    if distance.text.is_empty() {
+        return Err(distance.error("rotation distance"));
+    }
+
+    distance.parse("rotation distance fitting in a u64")
+}
+
+fn parse_rotation(rotation: Token) -> Result<Rotation, ParseError> {
+    let distance = || parse_distance(rotation.slice(1, rotation.text.len()));
+    match rotation.text.as_bytes().first() {
+        Some(b'L') => Ok(Rotation::Left(distance()?)),
+        Some(b'R') => Ok(Rotation::Right(distance()?)),
+        _ => Err(rotation.error("rotation starting with `L` or `R`")),
+    }
+}
+
+impl Rotation {
+    /// Clicks of the rotation, whatever its direction.
+    #[must_use]
+    pub const fn distance(self) -> u64 {
+        match self {
+            Self::Left(distance) | Self::Right(distance) => distance,
         }
     }
-    let mut result = 0;
-    let mut dial = 50;
-    for rotation in rotations {
-        match rotation {
-            Rotation::Left(rotate) => {
-                let rotate = rotate % 100;
-                if dial < rotate {
-                    dial += 100 - rotate;
-                } else {
-                    dial -= rotate;
-                }
-                if dial == 0 {
-                    result += 1;
-                }
-            }
-            Rotation::Right(rotate) => {
-                let rotate = rotate % 100;
-                dial = (dial + rotate) % 100;
-                if dial == 0 {
-                    result += 1;
-                }
-            }
+}
+
+impl FromStr for Rotation {
+    type Err = ParseError;
+
+    /// Parses a single rotation, the error positions are relative to `rotation`.
+    fn from_str(rotation: &str) -> Result<Self, Self::Err> {
+        parse_rotation(Token::new(rotation, 1, 1))
+    }
+}
+
+impl fmt::Display for Rotation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Left(distance) => write!(f, "L{distance}"),
+            Self::Right(distance) => write!(f, "R{distance}"),
         }
     }
+}
 
-    result
+fn convert_rotation(rotation: &str) -> Option<Rotation> {
+    rotation.parse().ok()
+}
+
+/// Parses whitespace separated rotations such as `L68 R48`.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on the first word that is not a rotation.
+pub fn parse_rotations(input: &str) -> Result<Vec<Rotation>, ParseError> {
+    words(input).map(parse_rotation).collect()
 }
 
+/// Same as [`parse_rotations`] but silently skips the words that are not rotations.
 #[must_use]
-pub fn solve_part_2(input: Vec<&str>) -> u64 {
+pub fn parse_rotations_lenient(input: &str) -> Vec<Rotation> {
     let mut rotations = vec![];
-    for rotation in input {
+    for rotation in input.split_whitespace() {
         if let Some(rotation) = convert_rotation(rotation) {
             rotations.push(rotation);
         }
     }
-    let mut result = 0;
-    let mut dial = 50;
-    for rotation in rotations {
-        match rotation {
-            Rotation::Left(rotate) => {
-                result += rotate / 100;
-                let rotate = rotate % 100;
-                if rotate > 0 {
-                    match dial.cmp(&rotate) {
-                        Ordering::Equal => {
-                            result += 1;
-                            dial = 0;
-                        }
-                        Ordering::Less => {
-                            if dial != 0 {
-                                result += 1;
-                            }
-                            dial += 100 - rotate;
-                        }
-                        Ordering::Greater => {
-                            dial -= rotate;
-                        }
-                    }
-                }
-            }
-            Rotation::Right(rotate) => {
-                result += rotate / 100;
-                let rotate = rotate % 100;
-                let diff = dial + rotate;
-                result += diff / 100;
-                dial = diff % 100;
-            }
-        }
+
+    rotations
+}
+
+/// Counts the rotations leaving the dial of the puzzle on zero, see [`Dial::default`].
+///
+/// # Errors
+///
+/// Never fails for now, the count is bounded by the number of rotations.
+pub fn solve_part_1(rotations: &[Rotation]) -> Result<u64, SolveError> {
+    Dial::default().count(rotations.iter().copied(), Counting::LandOn)
+}
+
+/// Counts every time the dial of the puzzle points at zero, including while rotating.
+///
+/// # Errors
+///
+/// Returns [`SolveError::Overflow`] when the count does not fit in a `u64`.
+pub fn solve_part_2(rotations: &[Rotation]) -> Result<u64, SolveError> {
+    Dial::default().count(rotations.iter().copied(), Counting::PassThrough)
+}
+
+pub struct Day1;
+
+impl Solution for Day1 {
+    const DAY: u8 = 1;
+
+    type Input = Vec<Rotation>;
+    type Answer = u64;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_rotations(input)
+    }
+
+    fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_1(input)
     }
 
-    result
+    fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use common::{GoldenError, check_inputs};
+
     use super::*;
 
     #[test]
-    fn example_solve_part_1() {
-        let input = vec![
-            "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82",
-        ];
-        let result = solve_part_1(input);
-        assert_eq!(result, 3);
+    fn example_solve_part_1() -> Result<(), ParseError> {
+        let input = parse_rotations("L68 L30 R48 L5 R60 L55 L1 L99 R14 L82")?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Ok(3));
+
+        Ok(())
     }
 
     #[test]
-    fn example_solve_part_2() {
-        let input = vec!["L68"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 1);
-        let input = vec!["L68", "L30"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 1);
-        let input = vec!["L68", "L30", "R48"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 2);
-        let input = vec!["L68", "L30", "R48", "L5"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 2);
-        let input = vec!["L68", "L30", "R48", "L5", "R60"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 3);
-        let input = vec!["L68", "L30", "R48", "L5", "R60", "L55"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 4);
-        let input = vec!["L68", "L30", "R48", "L5", "R60", "L55", "L1"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 4);
-        let input = vec!["L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 5);
-        let input = vec!["L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 5);
-        let input = vec![
-            "L68", "L30", "R48", "L5", "R60", "L55", "L1", "L99", "R14", "L82",
-        ];
-        let result = solve_part_2(input);
-        assert_eq!(result, 6);
+    fn example_solve_part_2() -> Result<(), ParseError> {
+        let input = parse_rotations("L68")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(1));
+        let input = parse_rotations("L68 L30")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(1));
+        let input = parse_rotations("L68 L30 R48")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(2));
+        let input = parse_rotations("L68 L30 R48 L5")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(2));
+        let input = parse_rotations("L68 L30 R48 L5 R60")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(3));
+        let input = parse_rotations("L68 L30 R48 L5 R60 L55")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(4));
+        let input = parse_rotations("L68 L30 R48 L5 R60 L55 L1")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(4));
+        let input = parse_rotations("L68 L30 R48 L5 R60 L55 L1 L99")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(5));
+        let input = parse_rotations("L68 L30 R48 L5 R60 L55 L1 L99 R14")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(5));
+        let input = parse_rotations("L68 L30 R48 L5 R60 L55 L1 L99 R14 L82")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(6));
+
+        Ok(())
     }
 
     #[test]
-    fn left_limit_solve_part_2() {
-        let input = vec!["L1000"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 10);
-        let input = vec!["L100"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 1);
-        let input = vec!["L50"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 1);
+    fn left_limit_solve_part_2() -> Result<(), ParseError> {
+        let input = parse_rotations("L1000")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(10));
+        let input = parse_rotations("L100")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(1));
+        let input = parse_rotations("L50")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(1));
+
+        Ok(())
     }
 
     #[test]
-    fn right_limit_solve_part_2() {
-        let input = vec!["R1000"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 10);
-        let input = vec!["R100"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 1);
-        let input = vec!["R50"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 1);
+    fn right_limit_solve_part_2() -> Result<(), ParseError> {
+        let input = parse_rotations("R1000")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(10));
+        let input = parse_rotations("R100")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(1));
+        let input = parse_rotations("R50")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(1));
+
+        Ok(())
     }
 
     #[test]
@@ -184,4 +255,67 @@ mod tests {
         let result = convert_rotation(input);
         assert_eq!(result, Some(Rotation::Right(43)));
     }
+
+    #[test]
+    fn rotation_from_str() -> Result<(), ParseError> {
+        let result: Rotation = "L42".parse()?;
+        assert_eq!(result, Rotation::Left(42));
+        assert_eq!(result.to_string(), "L42");
+        assert_eq!(Rotation::Right(7).to_string(), "R7");
+        let result = "L12x".parse::<Rotation>().map_err(|err| err.to_string());
+        assert_eq!(
+            result,
+            Err("line 1, column 4: expected digit of the rotation distance, found `x`".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_parse_rotations() {
+        let result = parse_rotations("L68 L30\nR48 X5");
+        assert_eq!(
+            result,
+            Err(ParseError::new(
+                2,
+                5,
+                "rotation starting with `L` or `R`",
+                "X5"
+            ))
+        );
+        let result = parse_rotations("L68 L12x");
+        assert_eq!(
+            result,
+            Err(ParseError::new(1, 8, "digit of the rotation distance", "x"))
+        );
+        let result = parse_rotations("L68\n R");
+        assert_eq!(result, Err(ParseError::new(2, 3, "rotation distance", "")));
+        let result = parse_rotations("R18446744073709551616");
+        assert_eq!(
+            result,
+            Err(ParseError::new(
+                1,
+                2,
+                "rotation distance fitting in a u64",
+                "18446744073709551616"
+            ))
+        );
+        let result = parse_rotations_lenient("L68 L12x R5");
+        assert_eq!(result, vec![Rotation::Left(68), Rotation::Right(5)]);
+    }
+
+    #[test]
+    fn overflow_solve_part_2() {
+        let input = vec![Rotation::Right(u64::MAX); 101];
+        let result = solve_part_2(&input);
+        assert_eq!(result, Err(SolveError::Overflow));
+    }
+
+    #[test]
+    fn golden_inputs() -> Result<(), GoldenError> {
+        let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        check_inputs::<Day1>(&inputs)?;
+
+        Ok(())
+    }
 }
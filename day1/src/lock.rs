@@ -0,0 +1,301 @@
+use std::collections::VecDeque;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use common::{ParseError, Token, words};
+
+use crate::{Dial, Rotation, parse_rotation};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum LockError {
+    /// An instruction or a gear names a dial the lock does not have, dials
+    /// being numbered from 1.
+    UnknownDial { dial: usize, dials: usize },
+    /// The gears turning this dial end up turning it again.
+    GearCycle { dial: usize },
+    /// A count or a geared distance does not fit in a `u64`.
+    Overflow,
+}
+
+impl fmt::Display for LockError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownDial { dial, dials } => {
+                write!(f, "no dial {dial} on a lock of {dials} dials")
+            }
+            Self::GearCycle { dial } => write!(f, "dial {dial} is geared to itself"),
+            Self::Overflow => write!(f, "a count or a geared distance overflows a u64"),
+        }
+    }
+}
+
+impl Error for LockError {}
+
+/// A rotation of one dial of a lock, written `2:L68`. Dials are numbered from 1.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Instruction {
+    pub dial: usize,
+    pub rotation: Rotation,
+}
+
+fn parse_instruction(instruction: Token) -> Result<Instruction, ParseError> {
+    let Some((dial, rotation)) = instruction.split_once(':') else {
+        return Err(instruction.error("instruction `dial:rotation`"));
+    };
+
+    Ok(Instruction {
+        dial: dial.parse("dial number")?,
+        rotation: parse_rotation(rotation)?,
+    })
+}
+
+impl FromStr for Instruction {
+    type Err = ParseError;
+
+    fn from_str(instruction: &str) -> Result<Self, Self::Err> {
+        parse_instruction(Token::new(instruction, 1, 1))
+    }
+}
+
+impl fmt::Display for Instruction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.dial, self.rotation)
+    }
+}
+
+/// Parses whitespace separated instructions such as `1:L68 2:R48`.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on the first word that is not an instruction.
+pub fn parse_instructions(input: &str) -> Result<Vec<Instruction>, ParseError> {
+    words(input).map(parse_instruction).collect()
+}
+
+/// Couples two dials: every click of `driver` turns `driven` by `ratio` clicks,
+/// the other way round when `reversed`.
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
+pub struct Gear {
+    pub driver: usize,
+    pub driven: usize,
+    pub ratio: u64,
+    pub reversed: bool,
+}
+
+/// What [`Lock::run`] saw happen to each dial.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct LockReport {
+    /// Per dial, the instructions leaving it on a target after turning it.
+    pub landings: Vec<u64>,
+    /// Per dial, the clicks pointing it at a target, while rotating included.
+    pub passes: Vec<u64>,
+    /// Index of the first instruction after which every dial is on a target.
+    pub all_on_target: Option<usize>,
+    /// The dials as left by the last instruction.
+    pub dials: Vec<Dial>,
+}
+
+/// Several dials, possibly geared together.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Lock {
+    dials: Vec<Dial>,
+    gears: Vec<Gear>,
+}
+
+impl Lock {
+    /// # Errors
+    ///
+    /// Returns [`LockError::UnknownDial`] when a gear names a missing dial and
+    /// [`LockError::GearCycle`] when the gears turn a dial back on itself.
+    pub fn new(dials: Vec<Dial>, gears: Vec<Gear>) -> Result<Self, LockError> {
+        let lock = Self { dials, gears };
+        for gear in &lock.gears {
+            lock.check_dial(gear.driver)?;
+            lock.check_dial(gear.driven)?;
+        }
+        for dial in 1..=lock.dials.len() {
+            let mut seen = vec![false; lock.dials.len() + 1];
+            let mut stack = vec![dial];
+            while let Some(driver) = stack.pop() {
+                for gear in lock.gears.iter().filter(|gear| gear.driver == driver) {
+                    if gear.driven == dial {
+                        return Err(LockError::GearCycle { dial });
+                    }
+                    if !seen[gear.driven] {
+                        seen[gear.driven] = true;
+                        stack.push(gear.driven);
+                    }
+                }
+            }
+        }
+
+        Ok(lock)
+    }
+
+    const fn check_dial(&self, dial: usize) -> Result<(), LockError> {
+        if dial == 0 || dial > self.dials.len() {
+            return Err(LockError::UnknownDial {
+                dial,
+                dials: self.dials.len(),
+            });
+        }
+
+        Ok(())
+    }
+
+    #[must_use]
+    pub fn dials(&self) -> &[Dial] {
+        &self.dials
+    }
+
+    /// Turns the dial of `instruction` and every dial geared to it, adding the
+    /// passes to `report` and marking the dials that moved.
+    fn apply(
+        &self,
+        instruction: Instruction,
+        report: &mut LockReport,
+        moved: &mut [bool],
+    ) -> Result<(), LockError> {
+        let mut queue = VecDeque::from([(instruction.dial, instruction.rotation)]);
+        while let Some((dial, rotation)) = queue.pop_front() {
+            let index = dial - 1;
+            let passes = report.dials[index].rotate(rotation);
+            report.passes[index] = report.passes[index]
+                .checked_add(passes)
+                .ok_or(LockError::Overflow)?;
+            moved[index] = true;
+
+            for gear in self.gears.iter().filter(|gear| gear.driver == dial) {
+                let distance = rotation
+                    .distance()
+                    .checked_mul(gear.ratio)
+                    .ok_or(LockError::Overflow)?;
+                let rotation = match (rotation, gear.reversed) {
+                    (Rotation::Left(_), false) | (Rotation::Right(_), true) => {
+                        Rotation::Left(distance)
+                    }
+                    (Rotation::Right(_), false) | (Rotation::Left(_), true) => {
+                        Rotation::Right(distance)
+                    }
+                };
+                queue.push_back((gear.driven, rotation));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Runs the instructions from the current positions of the dials. The lock
+    /// itself does not move.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`LockError::UnknownDial`] when an instruction names a missing
+    /// dial and [`LockError::Overflow`] when a count or a geared distance does
+    /// not fit in a `u64`.
+    pub fn run(&self, instructions: &[Instruction]) -> Result<LockReport, LockError> {
+        let mut report = LockReport {
+            landings: vec![0; self.dials.len()],
+            passes: vec![0; self.dials.len()],
+            all_on_target: None,
+            dials: self.dials.clone(),
+        };
+
+        for (step, &instruction) in instructions.iter().enumerate() {
+            self.check_dial(instruction.dial)?;
+            let mut moved = vec![false; self.dials.len()];
+            self.apply(instruction, &mut report, &mut moved)?;
+
+            for (index, dial) in report.dials.iter().enumerate() {
+                if moved[index] && dial.is_on_target() {
+                    report.landings[index] = report.landings[index]
+                        .checked_add(1)
+                        .ok_or(LockError::Overflow)?;
+                }
+            }
+            if report.all_on_target.is_none() && report.dials.iter().all(Dial::is_on_target) {
+                report.all_on_target = Some(step);
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_lock_instructions() -> Result<(), ParseError> {
+        let result = parse_instructions("1:L68\n 2:R48")?;
+        assert_eq!(
+            result,
+            vec![
+                Instruction {
+                    dial: 1,
+                    rotation: Rotation::Left(68),
+                },
+                Instruction {
+                    dial: 2,
+                    rotation: Rotation::Right(48),
+                },
+            ]
+        );
+        assert_eq!(result[1].to_string(), "2:R48");
+        let result = parse_instructions("1:L68 L30");
+        assert_eq!(
+            result,
+            Err(ParseError::new(1, 7, "instruction `dial:rotation`", "L30"))
+        );
+        let result = parse_instructions("x:L68");
+        assert_eq!(result, Err(ParseError::new(1, 1, "dial number", "x")));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_lock() {
+        let dials = vec![Dial::default(), Dial::default()];
+        let gear = |driver, driven| Gear {
+            driver,
+            driven,
+            ratio: 1,
+            reversed: false,
+        };
+        let result = Lock::new(dials.clone(), vec![gear(1, 3)]);
+        assert_eq!(result, Err(LockError::UnknownDial { dial: 3, dials: 2 }));
+        let result = Lock::new(dials.clone(), vec![gear(1, 2), gear(2, 1)]);
+        assert_eq!(result, Err(LockError::GearCycle { dial: 1 }));
+        let result = Lock::new(dials, vec![]).and_then(|lock| {
+            lock.run(&[Instruction {
+                dial: 0,
+                rotation: Rotation::Left(1),
+            }])
+        });
+        assert_eq!(result, Err(LockError::UnknownDial { dial: 0, dials: 2 }));
+    }
+
+    #[test]
+    fn geared_lock() -> Result<(), Box<dyn Error>> {
+        let lock = Lock::new(
+            vec![Dial::default(), Dial::new(10, 0, [0])?, Dial::default()],
+            vec![Gear {
+                driver: 1,
+                driven: 2,
+                ratio: 2,
+                reversed: true,
+            }],
+        )?;
+        let instructions = parse_instructions("1:L50 3:R50 1:R25 3:L25 1:L25")?;
+        let report = lock.run(&instructions)?;
+        assert_eq!(report.landings, vec![2, 3, 1]);
+        assert_eq!(report.passes, vec![2, 20, 1]);
+        assert_eq!(report.all_on_target, Some(1));
+        let positions: Vec<u64> = report.dials.iter().map(Dial::position).collect();
+        assert_eq!(positions, vec![0, 0, 75]);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,197 @@
+use std::io::{self, BufRead};
+
+use common::Token;
+
+use crate::{Counting, Dial, Rotation, SolveError, parse_rotation};
+
+/// Why streaming the input failed.
+pub type StreamError = common::StreamError<SolveError>;
+
+impl From<SolveError> for StreamError {
+    fn from(err: SolveError) -> Self {
+        Self::Solve(err)
+    }
+}
+
+/// Rotations read word by word from a [`BufRead`], built by [`read_rotations`].
+/// Only the current word is held in memory, however long the lines are. Stops
+/// after the first error.
+#[derive(Debug)]
+pub struct Rotations<R> {
+    reader: R,
+    word: Vec<u8>,
+    line: usize,
+    column: usize,
+    done: bool,
+}
+
+/// Streams the whitespace separated rotations of `reader`, such as `L68 R48`.
+pub const fn read_rotations<R: BufRead>(reader: R) -> Rotations<R> {
+    Rotations {
+        reader,
+        word: Vec::new(),
+        line: 1,
+        column: 1,
+        done: false,
+    }
+}
+
+impl<R: BufRead> Rotations<R> {
+    /// Reads the next word into `self.word`, returning the line and column it
+    /// starts at, or `None` at the end of the input.
+    fn read_word(&mut self) -> io::Result<Option<(usize, usize)>> {
+        self.word.clear();
+        let mut start = None;
+        loop {
+            let buf = match self.reader.fill_buf() {
+                Ok(buf) => buf,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => return Err(err),
+            };
+            if buf.is_empty() {
+                return Ok(start);
+            }
+
+            let mut used = 0;
+            for &byte in buf {
+                if byte.is_ascii_whitespace() {
+                    if start.is_some() {
+                        self.reader.consume(used);
+                        return Ok(start);
+                    }
+                    if byte == b'\n' {
+                        self.line += 1;
+                        self.column = 1;
+                    } else {
+                        self.column += 1;
+                    }
+                } else {
+                    start.get_or_insert((self.line, self.column));
+                    self.word.push(byte);
+                    // Columns count characters, not the bytes continuing one.
+                    if byte & 0xc0 != 0x80 {
+                        self.column += 1;
+                    }
+                }
+                used += 1;
+            }
+            self.reader.consume(used);
+        }
+    }
+}
+
+impl<R: BufRead> Iterator for Rotations<R> {
+    type Item = Result<Rotation, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        let result = match self.read_word() {
+            Ok(None) => {
+                self.done = true;
+                return None;
+            }
+            Ok(Some((line, column))) => match str::from_utf8(&self.word) {
+                Ok(text) => {
+                    parse_rotation(Token::new(text, line, column)).map_err(StreamError::Parse)
+                }
+                Err(err) => Err(StreamError::Io(io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    err,
+                ))),
+            },
+            Err(err) => Err(err.into()),
+        };
+        self.done = result.is_err();
+        Some(result)
+    }
+}
+
+/// Counts what `counting` asks for while streaming the rotations of `reader`
+/// through `dial`, without holding the whole input in memory.
+///
+/// # Errors
+///
+/// Returns a [`StreamError`] when reading fails, on the first word that is not
+/// a rotation and when the count does not fit in a `u64`.
+pub fn try_solve_reader(
+    reader: impl BufRead,
+    dial: &Dial,
+    counting: Counting,
+) -> Result<u64, StreamError> {
+    let mut error = None;
+    let rotations = read_rotations(reader).map_while(|rotation| match rotation {
+        Ok(rotation) => Some(rotation),
+        Err(err) => {
+            error = Some(err);
+            None
+        }
+    });
+    let result = dial.count(rotations, counting);
+
+    match error {
+        Some(err) => Err(err),
+        None => Ok(result?),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::io::BufReader;
+
+    use super::*;
+    use crate::parse_rotations;
+
+    #[test]
+    fn stream_rotations() -> Result<(), StreamError> {
+        let input = "L68 L30\r\nR48\n\nL5 R60 L55 L1 L99 R14 L82";
+        let result = try_solve_reader(input.as_bytes(), &Dial::default(), Counting::LandOn)?;
+        assert_eq!(result, 3);
+        let result = try_solve_reader(input.as_bytes(), &Dial::default(), Counting::PassThrough)?;
+        assert_eq!(result, 6);
+
+        Ok(())
+    }
+
+    #[test]
+    fn rotations_across_buffers() -> Result<(), Box<dyn Error>> {
+        let input = "L68 L30\r\nR48   L5\tR60 L55 L1 L99 R14 L82\n";
+        let reader = BufReader::with_capacity(3, input.as_bytes());
+        let result = read_rotations(reader).collect::<Result<Vec<_>, _>>()?;
+        assert_eq!(result, parse_rotations(input)?);
+        let reader = BufReader::with_capacity(2, "R1 L2 R3\n R4 L5x".as_bytes());
+        let result = read_rotations(reader)
+            .last()
+            .map(|rotation| rotation.map_err(|err| err.to_string()));
+        assert_eq!(
+            result,
+            Some(Err(
+                "line 2, column 7: expected digit of the rotation distance, found `x`".to_string()
+            ))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_stream() {
+        let result: Vec<String> = read_rotations("L68\nR4 L12x R5\nL1".as_bytes())
+            .map(|rotation| match rotation {
+                Ok(rotation) => rotation.to_string(),
+                Err(err) => err.to_string(),
+            })
+            .collect();
+        assert_eq!(
+            result,
+            vec![
+                "L68",
+                "R4",
+                "line 2, column 7: expected digit of the rotation distance, found `x`",
+            ]
+        );
+        let result = try_solve_reader(&[0xff, b'\n'][..], &Dial::default(), Counting::LandOn);
+        assert!(matches!(result, Err(StreamError::Io(_))));
+    }
+}
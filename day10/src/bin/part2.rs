@@ -1,16 +1,15 @@
-use std::io::{self, Read};
+use std::error::Error;
+use std::process::ExitCode;
 
-use day10::{Machine, parse_machines, solve_part_2};
+use common::solve_inputs;
+use day10::{parse_machines, solve_part_2};
 
-fn get_input() -> Vec<Machine> {
-    let mut buf = String::new();
-    let _ = io::stdin().read_to_string(&mut buf);
+fn solve(input: &str) -> Result<u64, Box<dyn Error>> {
+    let input = parse_machines(input)?;
 
-    parse_machines(&buf)
+    Ok(solve_part_2(&input)?)
 }
 
-fn main() {
-    let input = get_input();
-    let result = solve_part_2(&input);
-    println!("{result}");
+fn main() -> ExitCode {
+    solve_inputs(solve)
 }
@@ -0,0 +1,103 @@
+use std::fmt::Write;
+
+use common::Rng;
+
+/// Size knobs of [`generate`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub machines: usize,
+    /// Most indicator lights, and joltage counters, of a machine.
+    pub max_lights: usize,
+    /// Most buttons of a machine.
+    pub max_buttons: usize,
+    /// Most presses of each button needed to reach the joltage requirements.
+    pub max_presses: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            machines: 150,
+            max_lights: 10,
+            max_buttons: 13,
+            max_presses: 20,
+        }
+    }
+}
+
+/// Generates one machine per line. The lights are the ones turned on by a random
+/// set of buttons and the joltages the counters reached by random presses, so
+/// every machine can be configured.
+#[must_use]
+pub fn generate(seed: u64, options: &Options) -> String {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    for _ in 0..options.machines {
+        let lights_len = 1 + rng.index(options.max_lights.max(1));
+        let buttons_len = 1 + rng.index(options.max_buttons.max(1));
+
+        let mut lights = vec![false; lights_len];
+        let mut joltages = vec![0; lights_len];
+        let mut buttons = vec![];
+        for _ in 0..buttons_len {
+            let mut wiring: Vec<usize> = (0..lights_len).filter(|_| rng.chance(40)).collect();
+            if wiring.is_empty() {
+                wiring.push(rng.index(lights_len));
+            }
+            if rng.chance(50) {
+                for &light in &wiring {
+                    lights[light] = !lights[light];
+                }
+            }
+            let presses = rng.range(0..=options.max_presses);
+            for &light in &wiring {
+                joltages[light] += presses;
+            }
+            let wiring: Vec<String> = wiring.iter().map(ToString::to_string).collect();
+            buttons.push(format!("({})", wiring.join(",")));
+        }
+
+        let lights: String = lights
+            .iter()
+            .map(|&on| if on { '#' } else { '.' })
+            .collect();
+        let joltages: Vec<String> = joltages.iter().map(ToString::to_string).collect();
+        let _ = writeln!(
+            input,
+            "[{lights}] {} {{{}}}",
+            buttons.join(" "),
+            joltages.join(",")
+        );
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{GeneratorError, check_generator};
+
+    use super::*;
+    use crate::{Day10, solve_part_1, solve_part_2};
+
+    #[test]
+    fn generated_input_parses() -> Result<(), GeneratorError> {
+        let options = Options::default();
+        for input in check_generator::<Day10>(|seed| generate(seed, &options))? {
+            assert_eq!(input.len(), options.machines);
+        }
+
+        let options = Options {
+            machines: 10,
+            max_lights: 4,
+            max_buttons: 5,
+            max_presses: 3,
+        };
+        for input in check_generator::<Day10>(|seed| generate(seed, &options))? {
+            assert!(solve_part_1(&input).is_ok());
+            assert!(solve_part_2(&input).is_ok());
+        }
+
+        Ok(())
+    }
+}
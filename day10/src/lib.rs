@@ -1,19 +1,162 @@
+pub mod generate;
+pub mod reference;
+
+use std::error::Error;
+use std::fmt;
+
+use common::{ParseError, Solution, Token, lines};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveError {
+    /// A button of the machine at this index is wired to a missing light or counter.
+    InvalidButton {
+        machine: usize,
+        button: usize,
+        light: usize,
+    },
+    /// The machine at this index has too many buttons to try every combination.
+    TooManyButtons { machine: usize, buttons: usize },
+    /// No combination of button presses configures the machine at this index.
+    NoSolution { machine: usize },
+    /// The total of button presses does not fit in a `u64`.
+    Overflow,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidButton {
+                machine,
+                button,
+                light,
+            } => write!(
+                f,
+                "button {button} of machine {machine} is wired to missing light {light}"
+            ),
+            Self::TooManyButtons { machine, buttons } => {
+                write!(f, "machine {machine} has too many buttons ({buttons})")
+            }
+            Self::NoSolution { machine } => write!(f, "machine {machine} cannot be configured"),
+            Self::Overflow => write!(f, "the count of button presses overflows a u64"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+#[derive(Debug, Clone)]
 pub struct Machine {
     lights: Vec<bool>,
     buttons: Vec<Vec<usize>>,
     joltages: Vec<u64>,
 }
 
+fn is_enclosed(word: Token, open: char, close: char) -> bool {
+    word.text.len() >= 2 && word.text.starts_with(open) && word.text.ends_with(close)
+}
+
+fn inside(word: Token) -> Token {
+    word.slice(1, word.text.len() - 1)
+}
+
+fn parse_lights(word: Token) -> Result<Vec<bool>, ParseError> {
+    inside(word)
+        .chars()
+        .map(|(light, token)| match light {
+            '.' => Ok(false),
+            '#' => Ok(true),
+            _ => Err(token.error("`.` or `#`")),
+        })
+        .collect()
+}
+
+fn parse_button(word: Token, lights_len: usize) -> Result<Vec<usize>, ParseError> {
+    let mut affected_lights = vec![];
+    for affected_light in inside(word).split(',') {
+        let expected = format!("light index below {lights_len}");
+        let index = affected_light.parse::<usize>(&expected)?;
+        if index >= lights_len {
+            return Err(affected_light.error(expected));
+        }
+        affected_lights.push(index);
+    }
+
+    Ok(affected_lights)
+}
+
+fn parse_joltages(word: Token, lights_len: usize) -> Result<Vec<u64>, ParseError> {
+    let joltages = inside(word)
+        .split(',')
+        .map(|joltage| joltage.parse("joltage"))
+        .collect::<Result<Vec<u64>, ParseError>>()?;
+    if joltages.len() != lights_len {
+        return Err(word.error(format!("{lights_len} joltage requirements")));
+    }
+
+    Ok(joltages)
+}
+
+fn parse_machine(line: Token) -> Result<Machine, ParseError> {
+    let mut lights: Option<Vec<bool>> = None;
+    let mut buttons = vec![];
+    let mut joltages = None;
+
+    for word in line.words() {
+        match (&lights, &joltages) {
+            (None, _) if is_enclosed(word, '[', ']') => lights = Some(parse_lights(word)?),
+            (None, _) => return Err(word.error("indicator lights `[...]`")),
+            (Some(lights), None) if is_enclosed(word, '(', ')') => {
+                buttons.push(parse_button(word, lights.len())?);
+            }
+            (Some(lights), None) if is_enclosed(word, '{', '}') => {
+                joltages = Some(parse_joltages(word, lights.len())?);
+            }
+            (Some(_), None) => {
+                return Err(word.error("button wiring `(...)` or joltage requirements `{...}`"));
+            }
+            (Some(_), Some(_)) => return Err(word.error("end of line")),
+        }
+    }
+
+    let Some(lights) = lights else {
+        return Err(line.end().error("indicator lights `[...]`"));
+    };
+    let Some(joltages) = joltages else {
+        return Err(line.end().error("joltage requirements `{...}`"));
+    };
+
+    Ok(Machine {
+        lights,
+        buttons,
+        joltages,
+    })
+}
+
+/// Parses one machine per line: its indicator lights diagram, its button
+/// wiring schematics and its joltage requirements. Blank lines are ignored.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on the first word that does not fit the machine
+/// description, including buttons wired to missing lights.
+pub fn parse_machines(input: &str) -> Result<Vec<Machine>, ParseError> {
+    lines(input)
+        .filter(|line| !line.text.trim().is_empty())
+        .map(parse_machine)
+        .collect()
+}
+
+/// Same as [`parse_machines`] but silently skips the words that cannot be read.
 #[must_use]
-pub fn parse_machines(input: &str) -> Vec<Machine> {
+pub fn parse_machines_lenient(input: &str) -> Vec<Machine> {
     let mut machines = vec![];
-    for line in input.lines() {
+    for line in lines(input) {
         let mut lights = vec![];
         let mut buttons = vec![];
         let mut joltages = vec![];
-        for word in line.split_whitespace() {
-            if word.starts_with('[') && word.ends_with(']') {
-                for light in word[1..word.len() - 1].chars() {
+        for word in line.words() {
+            if is_enclosed(word, '[', ']') {
+                for light in inside(word).text.chars() {
                     match light {
                         '.' => lights.push(false),
                         '#' => lights.push(true),
@@ -21,8 +164,8 @@ pub fn parse_machines(input: &str) -> Vec<Machine> {
                     }
                 }
             }
-            if word.starts_with('(') && word.ends_with(')') {
-                let button = &word[1..word.len() - 1];
+            if is_enclosed(word, '(', ')') {
+                let button = inside(word).text;
                 let mut affected_lights = vec![];
                 for affected_light in button.split(',') {
                     if let Ok(affected_light) = affected_light.parse::<usize>() {
@@ -31,8 +174,8 @@ pub fn parse_machines(input: &str) -> Vec<Machine> {
                 }
                 buttons.push(affected_lights);
             }
-            if word.starts_with('{') && word.ends_with('}') {
-                for joltage in word[1..word.len() - 1].split(',') {
+            if is_enclosed(word, '{', '}') {
+                for joltage in inside(word).text.split(',') {
                     if let Ok(joltage) = joltage.parse::<u64>() {
                         joltages.push(joltage);
                     }
@@ -117,55 +260,109 @@ impl Iterator for WeakCompositions {
     }
 }
 
-#[allow(clippy::cast_possible_truncation)]
-fn create_combos(n: usize) -> Vec<Vec<bool>> {
-    let mut combos = vec![];
-    let mut combo = vec![false; n];
-
-    for _ in 0..2usize.pow(n as u32) {
-        combos.push(combo.clone());
-        for b in combo.iter_mut().take(n) {
-            if !*b {
-                *b = true;
-                break;
-            }
-            *b = false;
+fn check_buttons(index: usize, machine: &Machine, len: usize) -> Result<(), SolveError> {
+    for (button, affected) in machine.buttons.iter().enumerate() {
+        if let Some(&light) = affected.iter().find(|&&light| light >= len) {
+            return Err(SolveError::InvalidButton {
+                machine: index,
+                button,
+                light,
+            });
         }
     }
 
-    combos
+    Ok(())
 }
 
-fn solve_machine_1(machine: &Machine) -> u64 {
-    let mut result = machine.buttons.len() as u64;
-    let combos = create_combos(machine.buttons.len());
+/// Fewest presses turning on the lights of `machine`. Pressing a button twice
+/// cancels out, so the buttons pressed once solve a linear system over GF(2):
+/// it is reduced to echelon form and every assignment of its free buttons tried.
+fn solve_machine_1(index: usize, machine: &Machine) -> Result<u64, SolveError> {
+    check_buttons(index, machine, machine.lights.len())?;
+    let buttons = machine.buttons.len();
+
+    // One row per light: whether each button toggles it, then its expected state.
+    let mut rows: Vec<Vec<bool>> = machine
+        .lights
+        .iter()
+        .enumerate()
+        .map(|(light, &on)| {
+            let mut row: Vec<bool> = machine
+                .buttons
+                .iter()
+                .map(|wiring| wiring.iter().filter(|&&wired| wired == light).count() % 2 == 1)
+                .collect();
+            row.push(on);
+            row
+        })
+        .collect();
 
-    for combo in combos {
-        let mut lights = vec![false; machine.lights.len()];
-        for affected_button in combo.iter().enumerate() {
-            if *affected_button.1 {
-                for affected_light in &machine.buttons[affected_button.0] {
-                    lights[*affected_light] = !lights[*affected_light];
+    let mut pivots = vec![];
+    for col in 0..buttons {
+        let rank = pivots.len();
+        let Some(pivot) = (rank..rows.len()).find(|&row| rows[row][col]) else {
+            continue;
+        };
+        rows.swap(rank, pivot);
+        let pivot_row = rows[rank].clone();
+        for (index, row) in rows.iter_mut().enumerate() {
+            if index != rank && row[col] {
+                for (cell, pivot_cell) in row.iter_mut().zip(&pivot_row) {
+                    *cell ^= pivot_cell;
                 }
             }
         }
-        if lights == machine.lights {
-            let mut sum = 0;
-            for affected_button in combo {
-                if affected_button {
-                    sum += 1;
-                }
-            }
-            if sum <= result {
-                result = sum;
-            }
+        pivots.push(col);
+    }
+    if rows[pivots.len()..].iter().any(|row| row[buttons]) {
+        return Err(SolveError::NoSolution { machine: index });
+    }
+
+    let free: Vec<usize> = (0..buttons).filter(|col| !pivots.contains(col)).collect();
+    let combos = u32::try_from(free.len())
+        .ok()
+        .and_then(|free| 1u64.checked_shl(free))
+        .ok_or(SolveError::TooManyButtons {
+            machine: index,
+            buttons,
+        })?;
+
+    let mut result = u64::MAX;
+    let mut pressed = vec![false; buttons];
+    for combo in 0..combos {
+        for (bit, &col) in free.iter().enumerate() {
+            pressed[col] = combo >> bit & 1 == 1;
         }
+        for (row, &col) in rows.iter().zip(&pivots) {
+            pressed[col] = free
+                .iter()
+                .fold(row[buttons], |on, &free| on ^ (row[free] && pressed[free]));
+        }
+        result = result.min(pressed.iter().filter(|&&pressed| pressed).count() as u64);
     }
 
-    result
+    Ok(result)
 }
 
-#[allow(clippy::cast_precision_loss)]
+fn sum_presses(
+    machines: &[Machine],
+    solve_machine: impl Fn(usize, &Machine) -> Result<u64, SolveError>,
+) -> Result<u64, SolveError> {
+    let mut result: u64 = 0;
+    for (index, machine) in machines.iter().enumerate() {
+        let presses = solve_machine(index, machine)?;
+        result = result.checked_add(presses).ok_or(SolveError::Overflow)?;
+    }
+
+    Ok(result)
+}
+
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::needless_range_loop
+)]
 fn solve(buttons: &[Vec<usize>], c: &[u64], r: u64) -> Option<Vec<u64>> {
     let n_counters = c.len();
     let m_buttons = buttons.len();
@@ -188,7 +385,9 @@ fn solve(buttons: &[Vec<usize>], c: &[u64], r: u64) -> Option<Vec<u64>> {
     let mut pivot_cols = vec![-1isize; rows];
     let mut is_free_var = vec![true; m_buttons];
     for col in 0..m_buttons {
-        if pivot_row >= rows { break; }
+        if pivot_row >= rows {
+            break;
+        }
         let mut selected_row = pivot_row;
         while selected_row < rows && matrix[selected_row][col].abs() < 1e-9 {
             selected_row += 1;
@@ -214,7 +413,8 @@ fn solve(buttons: &[Vec<usize>], c: &[u64], r: u64) -> Option<Vec<u64>> {
             pivot_row += 1;
         }
     }
-    let free_indices: Vec<usize> = is_free_var.iter()
+    let free_indices: Vec<usize> = is_free_var
+        .iter()
         .enumerate()
         .filter_map(|(i, &is_free)| if is_free { Some(i) } else { None })
         .collect();
@@ -226,33 +426,40 @@ fn solve(buttons: &[Vec<usize>], c: &[u64], r: u64) -> Option<Vec<u64>> {
     }
 }
 
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_sign_loss,
+    clippy::needless_range_loop
+)]
 fn solve_free_vars(
     idx: usize,
     free_indices: &[usize],
     matrix: &[Vec<f64>],
     pivot_cols: &[isize],
     x: &mut [f64],
-    limit_r: u64
+    limit_r: u64,
 ) -> bool {
     if idx == free_indices.len() {
         for r in (0..matrix.len()).rev() {
             let p_col = pivot_cols[r];
-            if p_col != -1 {
+            if p_col == -1 {
+                let constant = matrix[r][matrix[0].len() - 1];
+                if constant.abs() > 1e-5 {
+                    return false;
+                }
+            } else {
                 let p_col = p_col as usize;
                 let mut sum = 0.0;
                 let constant = matrix[r][matrix[0].len() - 1];
                 for c in (p_col + 1)..x.len() {
                     sum += matrix[r][c] * x[c];
                 }
-                
+
                 let val = constant - sum;
                 if val < -1e-5 || (val.round() - val).abs() > 1e-5 {
                     return false;
                 }
                 x[p_col] = val.round();
-            } else {
-                let constant = matrix[r][matrix[0].len() - 1];
-                 if constant.abs() > 1e-5 { return false; }
             }
         }
         return true;
@@ -268,92 +475,206 @@ fn solve_free_vars(
     false
 }
 
-#[must_use]
-pub fn solve_part_1(machines: &[Machine]) -> u64 {
-    let mut result = 0;
-    for machine in machines {
-        result += solve_machine_1(machine);
-    }
-
-    result
+/// Sums the fewest button presses turning on the indicator lights of each machine.
+///
+/// # Errors
+///
+/// Returns [`SolveError::InvalidButton`] when a button is wired to a missing light,
+/// [`SolveError::TooManyButtons`] when the free buttons combinations cannot be
+/// enumerated and [`SolveError::NoSolution`] when no combination matches the lights.
+pub fn solve_part_1(machines: &[Machine]) -> Result<u64, SolveError> {
+    sum_presses(machines, solve_machine_1)
 }
 
-fn solve_machine_2(machine: &Machine) -> u64 {
-    let mut result = 0;
-    let sum_joltages: u64 = machine.joltages.iter().sum();
+fn solve_machine_2(index: usize, machine: &Machine) -> Result<u64, SolveError> {
+    check_buttons(index, machine, machine.joltages.len())?;
+    let sum_joltages = machine
+        .joltages
+        .iter()
+        .try_fold(0u64, |sum, joltage| sum.checked_add(*joltage))
+        .ok_or(SolveError::Overflow)?;
     for i in 0..=sum_joltages {
         if let Some(r) = solve(&machine.buttons, &machine.joltages, i) {
-            result = r.iter().sum();
-            break;
+            return r
+                .iter()
+                .try_fold(0u64, |sum, presses| sum.checked_add(*presses))
+                .ok_or(SolveError::Overflow);
         }
     }
 
-    result
+    Err(SolveError::NoSolution { machine: index })
 }
 
-#[must_use]
-pub fn solve_part_2(machines: &[Machine]) -> u64 {
-    let mut result = 0;
-    for machine in machines {
-        result += solve_machine_2(machine);
+/// Sums the fewest button presses reaching the joltage requirements of each machine.
+///
+/// # Errors
+///
+/// Returns [`SolveError::InvalidButton`] when a button is wired to a missing counter,
+/// [`SolveError::NoSolution`] when the requirements cannot be reached and
+/// [`SolveError::Overflow`] when the sum does not fit in a `u64`.
+pub fn solve_part_2(machines: &[Machine]) -> Result<u64, SolveError> {
+    sum_presses(machines, solve_machine_2)
+}
+
+pub struct Day10;
+
+impl Solution for Day10 {
+    const DAY: u8 = 10;
+
+    type Input = Vec<Machine>;
+    type Answer = u64;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_machines(input)
     }
 
-    result
+    fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_1(input)
+    }
+
+    fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use common::{GoldenError, check_inputs};
+
     use super::*;
 
     #[test]
-    fn example_solve_part_1() {
+    fn example_solve_part_1() -> Result<(), ParseError> {
         let input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
 [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
 [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
-        let input = parse_machines(input);
+        let input = parse_machines(input)?;
         let result = solve_part_1(&input);
-        assert_eq!(result, 2 + 3 + 2);
+        assert_eq!(result, Ok(2 + 3 + 2));
+
+        Ok(())
     }
 
     #[test]
-    fn example_solve_part_2() {
+    fn example_solve_part_2() -> Result<(), ParseError> {
         let input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
     [...#.] (0,2,3,4) (2,3) (0,4) (0,1,2) (1,2,3,4) {7,5,12,7,2}
     [.###.#] (0,1,2,3,4) (0,3,4) (0,1,2,4,5) (1,2) {10,11,11,5,10,5}";
-        let input = parse_machines(input);
+        let input = parse_machines(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 10 + 12 + 11);
+        assert_eq!(result, Ok(10 + 12 + 11));
+
+        Ok(())
     }
 
     #[test]
-    fn custom_solve_part_2() {
+    fn custom_solve_part_2() -> Result<(), ParseError> {
         let input = "[...] (0) (1) {0,0,0}";
-        let input = parse_machines(input);
+        let input = parse_machines(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 0);
+        assert_eq!(result, Ok(0));
         let input = "[...] (0) (1) (0) (1) {0,0,0}";
-        let input = parse_machines(input);
+        let input = parse_machines(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 0);
+        assert_eq!(result, Ok(0));
         let input = "[...] (0) (1) (2) {0,0,1}";
-        let input = parse_machines(input);
+        let input = parse_machines(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 1);
+        assert_eq!(result, Ok(1));
         let input = "[...] (0) (1) (2) {1,1,1}";
-        let input = parse_machines(input);
+        let input = parse_machines(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 3);
+        assert_eq!(result, Ok(3));
         let input = "[...] (0,1,2) {1,1,1}";
-        let input = parse_machines(input);
+        let input = parse_machines(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 1);
+        assert_eq!(result, Ok(1));
         let input = "[...] (0,1,2) {2,2,2}";
-        let input = parse_machines(input);
+        let input = parse_machines(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 2);
+        assert_eq!(result, Ok(2));
         let input = "[...] (0) (1) (2) (0,1,2) {2,2,2}";
-        let input = parse_machines(input);
+        let input = parse_machines(input)?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_parse_machines() {
+        let input = "[.##.] (3) (1,3) (2) (2,3) (0,2) (0,1) {3,5,4,7}
+[...#.] (0,2,3,4) (2,3) (0,5) (0,1,2) (1,2,3,4) {7,5,12,7,2}";
+        let result = parse_machines(input).err();
+        assert_eq!(
+            result,
+            Some(ParseError::new(2, 28, "light index below 5", "5"))
+        );
+        let input = "[.##.] (3) (1,3) {3,5,4}";
+        let result = parse_machines(input).err();
+        assert_eq!(
+            result,
+            Some(ParseError::new(1, 18, "4 joltage requirements", "{3,5,4}"))
+        );
+        let input = "[.##.] (3) (1,3)";
+        let result = parse_machines(input).err();
+        assert_eq!(
+            result,
+            Some(ParseError::new(1, 17, "joltage requirements `{...}`", ""))
+        );
+    }
+
+    #[test]
+    fn short_parse_machines_lenient() {
+        let input = parse_machines_lenient("[ [.#] ( (0) { {1,1}");
+        assert_eq!(input.len(), 1);
+        assert_eq!(input[0].lights, [false, true]);
+        assert_eq!(input[0].buttons, [[0]]);
+        assert_eq!(input[0].joltages, [1, 1]);
+    }
+
+    #[test]
+    fn invalid_solve_part_1() {
+        let input = parse_machines_lenient("[.#] (0,2) {1,1}");
+        let result = solve_part_1(&input);
+        assert_eq!(
+            result,
+            Err(SolveError::InvalidButton {
+                machine: 0,
+                button: 0,
+                light: 2
+            })
+        );
+        let input = parse_machines_lenient("[.#] (0) {1,1}");
+        let result = solve_part_1(&input);
+        assert_eq!(result, Err(SolveError::NoSolution { machine: 0 }));
+    }
+
+    #[test]
+    fn invalid_solve_part_2() {
+        let input = parse_machines_lenient("[.#] (0) (1) {1}");
+        let result = solve_part_2(&input);
+        assert_eq!(
+            result,
+            Err(SolveError::InvalidButton {
+                machine: 0,
+                button: 1,
+                light: 1
+            })
+        );
+        let input = parse_machines_lenient("[.#] (0) {1,1}");
         let result = solve_part_2(&input);
-        assert_eq!(result, 2);
+        assert_eq!(result, Err(SolveError::NoSolution { machine: 0 }));
+    }
+
+    #[test]
+    fn golden_inputs() -> Result<(), GoldenError> {
+        let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        check_inputs::<Day10>(&inputs)?;
+
+        Ok(())
     }
 }
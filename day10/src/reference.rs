@@ -0,0 +1,131 @@
+//! Brute force solver trying every combination of buttons, kept as an oracle
+//! for the fast one.
+
+use crate::{Machine, SolveError, check_buttons, sum_presses};
+
+fn create_combos(n: usize) -> Option<Vec<Vec<bool>>> {
+    let mut combos = vec![];
+    let mut combo = vec![false; n];
+
+    for _ in 0..2usize.checked_pow(u32::try_from(n).ok()?)? {
+        combos.push(combo.clone());
+        for b in combo.iter_mut().take(n) {
+            if !*b {
+                *b = true;
+                break;
+            }
+            *b = false;
+        }
+    }
+
+    Some(combos)
+}
+
+fn solve_machine(index: usize, machine: &Machine) -> Result<u64, SolveError> {
+    check_buttons(index, machine, machine.lights.len())?;
+    let mut result = None;
+    let combos = create_combos(machine.buttons.len()).ok_or(SolveError::TooManyButtons {
+        machine: index,
+        buttons: machine.buttons.len(),
+    })?;
+
+    for combo in combos {
+        let mut lights = vec![false; machine.lights.len()];
+        for affected_button in combo.iter().enumerate() {
+            if *affected_button.1 {
+                for affected_light in &machine.buttons[affected_button.0] {
+                    lights[*affected_light] = !lights[*affected_light];
+                }
+            }
+        }
+        if lights == machine.lights {
+            let mut sum = 0;
+            for affected_button in combo {
+                if affected_button {
+                    sum += 1;
+                }
+            }
+            if result.is_none_or(|result| sum <= result) {
+                result = Some(sum);
+            }
+        }
+    }
+
+    result.ok_or(SolveError::NoSolution { machine: index })
+}
+
+/// Fewest button presses configuring the indicator lights of every machine.
+///
+/// # Errors
+///
+/// Returns [`SolveError::InvalidButton`] when a button is wired to a missing light,
+/// [`SolveError::TooManyButtons`] when the button combinations cannot be enumerated
+/// and [`SolveError::NoSolution`] when no combination matches the lights.
+pub fn solve_part_1(machines: &[Machine]) -> Result<u64, SolveError> {
+    sum_presses(machines, solve_machine)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{Counterexample, Rng, differential};
+
+    use super::*;
+    use crate::generate::{Options, generate};
+    use crate::parse_machines;
+
+    type Case = Counterexample<Machine, Result<u64, SolveError>>;
+
+    /// The same machine with one button less.
+    fn simplify(machine: &Machine) -> Vec<Machine> {
+        (0..machine.buttons.len())
+            .map(|button| {
+                let mut simpler = machine.clone();
+                simpler.buttons.remove(button);
+                simpler
+            })
+            .collect()
+    }
+
+    /// Generated machines, with a light flipped here and there so that some of
+    /// them cannot be configured.
+    fn machines(seed: u64, options: &Options) -> Vec<Machine> {
+        let mut machines = parse_machines(&generate(seed, options))
+            .unwrap_or_else(|err| panic!("seed {seed} generated an invalid input: {err}"));
+        let mut rng = Rng::new(seed);
+        for machine in &mut machines {
+            if rng.chance(20) {
+                let light = rng.index(machine.lights.len());
+                machine.lights[light] = !machine.lights[light];
+            }
+        }
+        machines
+    }
+
+    fn check(options: &Options) -> Result<(), Case> {
+        differential(
+            0..100,
+            |seed| machines(seed, options),
+            simplify,
+            solve_part_1,
+            crate::solve_part_1,
+        )
+    }
+
+    #[test]
+    fn fast_matches_reference() -> Result<(), Case> {
+        check(&Options {
+            machines: 1,
+            max_lights: 6,
+            max_buttons: 10,
+            max_presses: 1,
+        })?;
+        check(&Options {
+            machines: 5,
+            max_lights: 10,
+            max_buttons: 13,
+            max_presses: 1,
+        })?;
+
+        Ok(())
+    }
+}
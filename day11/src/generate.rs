@@ -0,0 +1,92 @@
+use std::collections::HashSet;
+use std::fmt::Write;
+
+use common::Rng;
+
+/// Size knobs of [`generate`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Devices besides `svr`, `you`, `fft`, `dac` and `out`, at most a few thousands.
+    pub devices: usize,
+    /// Most outputs of a device.
+    pub max_outputs: usize,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            devices: 600,
+            max_outputs: 3,
+        }
+    }
+}
+
+const LETTERS: &[u8] = b"abcdefghijklmnopqrstuvwxyz";
+
+fn name(rng: &mut Rng) -> String {
+    (0..3)
+        .map(|_| char::from(rng.pick(LETTERS).copied().unwrap_or(b'a')))
+        .collect()
+}
+
+/// Generates a device graph without loops, one device per line followed by the
+/// devices its outputs are connected to. Devices only connect to the ones after
+/// them in a random order that starts with `svr` and ends with `out`.
+#[must_use]
+pub fn generate(seed: u64, options: &Options) -> String {
+    let mut rng = Rng::new(seed);
+    let devices = options.devices.min(17_000);
+    let mut names: HashSet<String> = ["svr", "you", "fft", "dac", "out"]
+        .into_iter()
+        .map(String::from)
+        .collect();
+    let mut order = vec![];
+    while order.len() < devices {
+        let name = name(&mut rng);
+        if names.insert(name.clone()) {
+            order.push(name);
+        }
+    }
+    for name in ["you", "fft", "dac"] {
+        let index = rng.index(order.len() + 1);
+        order.insert(index, name.to_string());
+    }
+    order.insert(0, "svr".to_string());
+    order.push("out".to_string());
+
+    let mut input = String::new();
+    for (index, device) in order.iter().enumerate().take(order.len() - 1) {
+        let later = &order[(index + 1)..];
+        let outputs_len = 1 + rng.index(options.max_outputs.max(1));
+        let mut outputs: Vec<&str> = vec![];
+        for _ in 0..outputs_len {
+            if let Some(output) = rng.pick(later)
+                && !outputs.contains(&output.as_str())
+            {
+                outputs.push(output);
+            }
+        }
+        let _ = writeln!(input, "{device}: {}", outputs.join(" "));
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{GeneratorError, check_generator};
+
+    use super::*;
+    use crate::{Day11, solve_part_1, solve_part_2};
+
+    #[test]
+    fn generated_input_parses() -> Result<(), GeneratorError> {
+        let options = Options::default();
+        for input in check_generator::<Day11>(|seed| generate(seed, &options))? {
+            assert!(solve_part_1(&input).is_ok());
+            assert!(solve_part_2(&input).is_ok());
+        }
+
+        Ok(())
+    }
+}
@@ -1,9 +1,41 @@
+pub mod generate;
+
 use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use common::{ParseError, Solution, lines};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveError {
+    /// The devices loop back to this one, making the count of paths endless.
+    Cycle { node: String },
+    /// The count of paths does not fit in the answer.
+    Overflow,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Cycle { node } => write!(f, "device `{node}` is part of a loop"),
+            Self::Overflow => write!(f, "the count of paths overflows"),
+        }
+    }
+}
+
+impl Error for SolveError {}
 
 pub struct Graph {
     adjacency_list: HashMap<String, Vec<String>>,
 }
 
+#[derive(Clone, Copy)]
+enum Visit {
+    New,
+    Visiting,
+    Done(u128),
+}
+
 struct FastGraph {
     adj: Vec<Vec<usize>>,
     names: Vec<String>,
@@ -49,40 +81,57 @@ impl Graph {
         }
     }
 
-    #[must_use]
-    pub fn count_paths_via(&self, start: &str, end: &str, waypoints: Vec<&str>) -> u128 {
+    /// Counts the paths from `start` to `end` going through every waypoint in order.
+    ///
+    /// # Errors
+    ///
+    /// Returns [`SolveError::Cycle`] when a loop can be reached on the way, as the
+    /// paths could then be endless, and [`SolveError::Overflow`] when the count does
+    /// not fit in a `u128`.
+    pub fn count_paths_via(
+        &self,
+        start: &str,
+        end: &str,
+        waypoints: Vec<&str>,
+    ) -> Result<u128, SolveError> {
         let g = self.optimize();
         let mut path_points = Vec::new();
+
         if let Some(&id) = g.indices.get(start) {
             path_points.push(id);
         } else {
-            return 0;
+            return Ok(0);
         }
+
         for wp in waypoints {
             if let Some(&id) = g.indices.get(wp) {
                 path_points.push(id);
             } else {
-                return 0;
+                return Ok(0);
             }
         }
+
         if let Some(&id) = g.indices.get(end) {
             path_points.push(id);
         } else {
-            return 0;
+            return Ok(0);
         }
+
         let mut total_combinations: u128 = 1;
         for i in 0..path_points.len() - 1 {
             let u = path_points[i];
             let v = path_points[i + 1];
-            let mut memo = vec![None; g.names.len()];
-            let segment_count = g.count_paths_dp(u, v, &mut memo);
+            let mut memo = vec![Visit::New; g.names.len()];
+            let segment_count = g.count_paths_dp(u, v, &mut memo)?;
             if segment_count == 0 {
-                return 0;
+                return Ok(0);
             }
-            total_combinations *= segment_count;
+            total_combinations = total_combinations
+                .checked_mul(segment_count)
+                .ok_or(SolveError::Overflow)?;
         }
 
-        total_combinations
+        Ok(total_combinations)
     }
 }
 
@@ -93,25 +142,97 @@ impl Default for Graph {
 }
 
 impl FastGraph {
-    fn count_paths_dp(&self, current: usize, target: usize, memo: &mut Vec<Option<u128>>) -> u128 {
-        if current == target {
-            return 1;
+    /// Counts the paths from `start` to `target` depth first, keeping for each
+    /// device on the way the next output to follow and the paths counted so far
+    /// on an explicit stack, so that a long chain cannot overflow the call stack.
+    fn count_paths_dp(
+        &self,
+        start: usize,
+        target: usize,
+        memo: &mut [Visit],
+    ) -> Result<u128, SolveError> {
+        if start == target {
+            return Ok(1);
         }
-        if let Some(res) = memo[current] {
-            return res;
+        match memo[start] {
+            Visit::New => {}
+            Visit::Visiting => {
+                return Err(SolveError::Cycle {
+                    node: self.names[start].clone(),
+                });
+            }
+            Visit::Done(count) => return Ok(count),
+        }
+
+        memo[start] = Visit::Visiting;
+        let mut stack: Vec<(usize, usize, u128)> = vec![(start, 0, 0)];
+        let mut result = 0;
+        while let Some((current, next, count)) = stack.last_mut() {
+            let Some(&neighbor) = self.adj[*current].get(*next) else {
+                memo[*current] = Visit::Done(*count);
+                let done = *count;
+                stack.pop();
+                match stack.last_mut() {
+                    Some((_, _, count)) => {
+                        *count = count.checked_add(done).ok_or(SolveError::Overflow)?;
+                    }
+                    None => result = done,
+                }
+                continue;
+            };
+            *next += 1;
+
+            let paths = if neighbor == target {
+                1
+            } else {
+                match memo[neighbor] {
+                    Visit::New => {
+                        memo[neighbor] = Visit::Visiting;
+                        stack.push((neighbor, 0, 0));
+                        continue;
+                    }
+                    Visit::Visiting => {
+                        return Err(SolveError::Cycle {
+                            node: self.names[neighbor].clone(),
+                        });
+                    }
+                    Visit::Done(count) => count,
+                }
+            };
+            *count = count.checked_add(paths).ok_or(SolveError::Overflow)?;
         }
 
-        let mut count: u128 = 0;
-        for &neighbor in &self.adj[current] {
-            count = count.saturating_add(self.count_paths_dp(neighbor, target, memo));
+        Ok(result)
+    }
+}
+
+/// Parses one device per line followed by the devices its outputs are
+/// connected to, such as `aaa: you hhh`. Blank lines are ignored.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on a line without `:` or without a device name.
+pub fn parse_graph(input: &str) -> Result<Graph, ParseError> {
+    let mut graph = Graph::new();
+    for line in lines(input).filter(|line| !line.text.trim().is_empty()) {
+        let Some((node, connected_nodes)) = line.split_once(':') else {
+            return Err(line.end().error("`:` after the device name"));
+        };
+        let node = node.trim();
+        if node.text.is_empty() || node.text.contains(char::is_whitespace) {
+            return Err(node.error("device name"));
+        }
+        for connected_node in connected_nodes.words() {
+            graph.add_edge(node.text, connected_node.text);
         }
-        memo[current] = Some(count);
-        count
     }
+
+    Ok(graph)
 }
 
+/// Same as [`parse_graph`] but silently skips the lines without `:`.
 #[must_use]
-pub fn parse_graph(input: &str) -> Graph {
+pub fn parse_graph_lenient(input: &str) -> Graph {
     let mut graph = Graph::new();
     for line in input.lines() {
         if let Some((node, connected_nodes)) = line.split_once(':') {
@@ -124,24 +245,60 @@ pub fn parse_graph(input: &str) -> Graph {
     graph
 }
 
-#[allow(clippy::cast_possible_truncation)]
-#[must_use]
-pub fn solve_part_1(graph: &Graph) -> u64 {
-    graph.count_paths_via("you", "out", vec![]) as u64
+/// Counts the paths from `you` to `out`.
+///
+/// # Errors
+///
+/// Returns [`SolveError::Cycle`] when a loop can be reached from `you` and
+/// [`SolveError::Overflow`] when the count does not fit in a `u64`.
+pub fn solve_part_1(graph: &Graph) -> Result<u64, SolveError> {
+    let result = graph.count_paths_via("you", "out", vec![])?;
+    u64::try_from(result).map_err(|_| SolveError::Overflow)
 }
 
-#[allow(clippy::cast_possible_truncation)]
-#[must_use]
-pub fn solve_part_2(graph: &Graph) -> u64 {
-    graph.count_paths_via("svr", "out", vec!["fft", "dac"]) as u64
+/// Counts the paths from `svr` to `out` visiting both `fft` and `dac`.
+///
+/// # Errors
+///
+/// Returns [`SolveError::Cycle`] when a loop can be reached from `svr` and
+/// [`SolveError::Overflow`] when the count does not fit in a `u64`.
+pub fn solve_part_2(graph: &Graph) -> Result<u64, SolveError> {
+    let result = graph.count_paths_via("svr", "out", vec!["fft", "dac"])?;
+    u64::try_from(result).map_err(|_| SolveError::Overflow)
+}
+
+pub struct Day11;
+
+impl Solution for Day11 {
+    const DAY: u8 = 11;
+
+    type Input = Graph;
+    type Answer = u64;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_graph(input)
+    }
+
+    fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_1(input)
+    }
+
+    fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use common::{GoldenError, check_inputs};
+
     use super::*;
 
     #[test]
-    fn example_solve_part_1() {
+    fn example_solve_part_1() -> Result<(), ParseError> {
         let input = "aaa: you hhh
 you: bbb ccc
 bbb: ddd eee
@@ -152,13 +309,15 @@ fff: out
 ggg: out
 hhh: ccc fff iii
 iii: out";
-        let input = parse_graph(input);
+        let input = parse_graph(input)?;
         let result = solve_part_1(&input);
-        assert_eq!(result, 5);
+        assert_eq!(result, Ok(5));
+
+        Ok(())
     }
 
     #[test]
-    fn example_solve_part_2() {
+    fn example_solve_part_2() -> Result<(), ParseError> {
         let input = "svr: aaa bbb
 aaa: fft
 fft: ccc
@@ -172,8 +331,59 @@ dac: fff
 fff: ggg hhh
 ggg: out
 hhh: out";
-        let input = parse_graph(input);
+        let input = parse_graph(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 2);
+        assert_eq!(result, Ok(2));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_parse_graph() {
+        let input = "aaa: you hhh
+you bbb ccc";
+        let result = parse_graph(input).err();
+        assert_eq!(
+            result,
+            Some(ParseError::new(2, 12, "`:` after the device name", ""))
+        );
+        let input = "aaa: you hhh
+: bbb ccc";
+        let result = parse_graph(input).err();
+        assert_eq!(result, Some(ParseError::new(2, 1, "device name", "")));
+    }
+
+    #[test]
+    fn cycle_solve_part_1() -> Result<(), ParseError> {
+        let input = "you: aaa
+aaa: bbb out
+bbb: aaa
+";
+        let input = parse_graph(input)?;
+        let result = solve_part_1(&input);
+        assert!(matches!(result, Err(SolveError::Cycle { .. })));
+
+        Ok(())
+    }
+
+    #[test]
+    fn long_chain_solve_part_1() {
+        let mut graph = Graph::new();
+        graph.add_edge("you", "d0");
+        for index in 1..100_000 {
+            graph.add_edge(&format!("d{}", index - 1), &format!("d{index}"));
+        }
+        graph.add_edge("d99999", "out");
+        graph.add_edge("d0", "out");
+        let result = solve_part_1(&graph);
+        assert_eq!(result, Ok(2));
+    }
+
+    #[test]
+    fn golden_inputs() -> Result<(), GoldenError> {
+        let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        check_inputs::<Day11>(&inputs)?;
+
+        Ok(())
     }
 }
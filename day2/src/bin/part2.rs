@@ -1,25 +1,24 @@
-use std::io::{self, Read};
+use std::error::Error;
+use std::process::ExitCode;
 
-use day2::solve_part_2;
+use common::{solve_args, take_flag};
+use day2::{Query, Reversed, normalize_ranges, parse_ranges, report_inputs, solve_part_2};
 
-fn parse_str(str: &str) -> Vec<String> {
-    let mut parsed_str = vec![];
-
-    for word in str.split(',') {
-        parsed_str.push(word.to_string());
+fn solve(input: &str) -> Result<u128, Box<dyn Error>> {
+    let ranges = parse_ranges(input)?;
+    let normalized = normalize_ranges(&ranges, Reversed::Swap)?;
+    for swapped in &normalized.swapped {
+        eprintln!("warning: {swapped}");
     }
-    parsed_str
-}
 
-fn get_input() -> Vec<String> {
-    let mut buf = String::new();
-    let _ = io::stdin().read_to_string(&mut buf);
-
-    parse_str(&buf)
+    Ok(solve_part_2(&normalized.ranges)?)
 }
 
-fn main() {
-    let input = get_input();
-    let result = solve_part_2(input.iter().map(std::string::String::as_str).collect());
-    println!("{result}");
+/// With `--csv`, every invalid ID of every input and why, as a single CSV.
+fn main() -> ExitCode {
+    let mut args = std::env::args_os().skip(1).collect();
+    if take_flag(&mut args, "--csv") {
+        return report_inputs(args, &Query::at_least_twice());
+    }
+    solve_args(args, solve)
 }
@@ -0,0 +1,56 @@
+use common::Rng;
+
+/// Size knobs of [`generate`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub ranges: usize,
+    /// Most digits of the first ID of a range, at most 19 to fit a `u64`.
+    pub max_digits: u32,
+    /// Most IDs in a range.
+    pub max_len: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            ranges: 40,
+            max_digits: 10,
+            max_len: 100_000,
+        }
+    }
+}
+
+/// Generates comma separated ID ranges such as `11-22,95-115`, on a single line.
+#[must_use]
+pub fn generate(seed: u64, options: &Options) -> String {
+    let mut rng = Rng::new(seed);
+    let max_digits = options.max_digits.clamp(1, 19);
+    let mut ranges = vec![];
+    for _ in 0..options.ranges {
+        let digits = u32::try_from(rng.range(1..=u64::from(max_digits))).unwrap_or(1);
+        let first = rng.range(10u64.pow(digits - 1)..=10u64.pow(digits) - 1);
+        let last = first.saturating_add(rng.range(0..=options.max_len.saturating_sub(1)));
+        ranges.push(format!("{first}-{last}"));
+    }
+
+    ranges.join(",") + "\n"
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{GeneratorError, check_generator};
+
+    use super::*;
+    use crate::Day2;
+
+    #[test]
+    fn generated_input_parses() -> Result<(), GeneratorError> {
+        let options = Options::default();
+        for input in check_generator::<Day2>(|seed| generate(seed, &options))? {
+            assert_eq!(input.len(), options.ranges);
+            assert!(input.iter().all(|range| range.0 <= range.1));
+        }
+
+        Ok(())
+    }
+}
@@ -1,153 +1,289 @@
-fn parse_range(range: &str) -> Option<(u64, u64)> {
-    if let Some(range) = range.split_once('-')
-        && let Ok(a) = range.0.parse::<u64>()
-        && let Ok(b) = range.1.parse::<u64>()
-    {
-        Some((a, b))
-    } else {
-        None
-    }
+pub mod generate;
+mod ranges;
+pub mod reference;
+mod repeated;
+mod report;
+
+use std::error::Error;
+use std::fmt;
+
+use common::{ParseError, Solution, Token, words};
+
+pub use ranges::{Id, Normalized, Reversed, Swapped, normalize_ranges};
+pub use repeated::{InvalidId, Query, QueryError, RepeatedIds, Repetition};
+pub use report::{RangeReport, report, report_csv, report_inputs};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveError {
+    /// The sum of the invalid IDs does not fit in the ID type.
+    Overflow,
+    /// The range at `index` has its first ID above its last.
+    ReversedRange {
+        index: usize,
+        first: u128,
+        last: u128,
+    },
 }
 
-#[must_use]
-pub fn solve_part_1(input: Vec<&str>) -> u64 {
-    let mut result = 0;
-    for range in input {
-        if let Some(range) = parse_range(range) {
-            for id in range.0..=range.1 {
-                let id_str = id.to_string();
-                let id_len = id_str.len();
-                if id_len % 2 == 0 {
-                    let a = &id_str[..(id_len / 2)];
-                    let b = &id_str[(id_len / 2)..];
-                    if a == b {
-                        result += id;
-                    }
-                }
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "the sum of invalid IDs overflows the ID type"),
+            Self::ReversedRange { index, first, last } => {
+                write!(f, "range {index} `{first}-{last}` is reversed")
             }
         }
     }
+}
 
-    result
+impl Error for SolveError {}
+
+fn parse_range<T: Id>(range: Token) -> Result<(T, T), ParseError> {
+    let Some((a, b)) = range.split_once('-') else {
+        return Err(range.error("ID range `first-last`"));
+    };
+
+    Ok((a.parse("first ID")?, b.parse("last ID")?))
 }
 
-fn is_magic(s: &str, steps: usize) -> bool {
-    let mut value = None;
-    for part in (0..s.len())
-        .step_by(steps)
-        .map(|i| &s[i..(i + steps).min(s.len())])
-    {
-        match value {
-            Some(value) => {
-                if value != part {
-                    return false;
-                }
-            }
-            None => value = Some(part),
-        }
-    }
-    true
+fn comma_separated(input: &str) -> impl Iterator<Item = Token<'_>> {
+    words(input).flat_map(|word| word.split(','))
+}
+
+/// Parses comma separated ID ranges such as `11-22,95-115`.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on the first item that is not an ID range.
+pub fn parse_ranges<T: Id>(input: &str) -> Result<Vec<(T, T)>, ParseError> {
+    comma_separated(input).map(parse_range).collect()
 }
 
+/// Same as [`parse_ranges`] but silently skips the items that are not ID ranges.
 #[must_use]
-pub fn solve_part_2(input: Vec<&str>) -> u64 {
-    let mut result = 0;
-    for range in input {
-        if let Some(range) = parse_range(range) {
-            for id in range.0..=range.1 {
-                let id_str = id.to_string();
-                let id_len = id_str.len();
-                for steps in 1..id_len {
-                    if id_len % steps == 0 && is_magic(&id_str, steps) {
-                        result += id;
-                        break;
-                    }
-                }
-            }
-        }
+pub fn parse_ranges_lenient<T: Id>(input: &str) -> Vec<(T, T)> {
+    comma_separated(input)
+        .filter_map(|range| parse_range(range).ok())
+        .collect()
+}
+
+fn sum_ranges<T: Id>(ranges: &[(T, T)], query: &Query) -> Result<T, SolveError> {
+    let ranges = normalize_ranges(ranges, Reversed::Reject)?.ranges;
+    let mut result: u128 = 0;
+    for (first, last) in ranges {
+        let sum = query.sum(first, last).ok_or(SolveError::Overflow)?;
+        result = result.checked_add(sum).ok_or(SolveError::Overflow)?;
     }
 
-    result
+    T::try_from(result).map_err(|_| SolveError::Overflow)
+}
+
+/// Sums the IDs made of a digit block repeated twice, without visiting every ID
+/// and counting the IDs of overlapping ranges once.
+///
+/// # Errors
+///
+/// Returns [`SolveError::ReversedRange`] for a range whose first ID is above its
+/// last and [`SolveError::Overflow`] when the sum does not fit in a `T`.
+pub fn solve_part_1<T: Id>(ranges: &[(T, T)]) -> Result<T, SolveError> {
+    sum_ranges(ranges, &Query::twice())
+}
+
+/// Sums the IDs made of a digit block repeated at least twice, without visiting
+/// every ID and counting the IDs of overlapping ranges once.
+///
+/// # Errors
+///
+/// Returns [`SolveError::ReversedRange`] for a range whose first ID is above its
+/// last and [`SolveError::Overflow`] when the sum does not fit in a `T`.
+pub fn solve_part_2<T: Id>(ranges: &[(T, T)]) -> Result<T, SolveError> {
+    sum_ranges(ranges, &Query::at_least_twice())
+}
+
+pub struct Day2;
+
+impl Solution for Day2 {
+    const DAY: u8 = 2;
+
+    type Input = Vec<(u128, u128)>;
+    type Answer = u128;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_ranges(input)
+    }
+
+    fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_1(input)
+    }
+
+    fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use common::{GoldenError, check_inputs};
+
     use super::*;
 
     #[test]
-    fn example_solve_part_1() {
-        let input = vec!["11-22"];
-        let result = solve_part_1(input);
-        assert_eq!(result, 11 + 22);
-        let input = vec!["11-22", "95-115"];
-        let result = solve_part_1(input);
-        assert_eq!(result, 11 + 22 + 99);
-        let input = vec![
-            "11-22",
-            "95-115",
-            "998-1012",
-            "1188511880-1188511890",
-            "222220-222224",
-            "1698522-1698528",
-            "446443-446449",
-            "38593856-38593862",
-            "565653-565659",
-            "824824821-824824827",
-            "2121212118-2121212124",
-        ];
-        let result = solve_part_1(input);
-        assert_eq!(result, 1227775554);
+    fn example_solve_part_1() -> Result<(), ParseError> {
+        let input = parse_ranges::<u64>("11-22")?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Ok(11 + 22));
+        let input = parse_ranges::<u64>("11-22,95-115")?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Ok(11 + 22 + 99));
+        let input = parse_ranges::<u64>(
+            "11-22,\
+             95-115,\
+             998-1012,\
+             1188511880-1188511890,\
+             222220-222224,\
+             1698522-1698528,\
+             446443-446449,\
+             38593856-38593862,\
+             565653-565659,\
+             824824821-824824827,\
+             2121212118-2121212124",
+        )?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Ok(1_227_775_554));
+
+        Ok(())
+    }
+
+    #[test]
+    fn example_solve_part_2() -> Result<(), ParseError> {
+        let input = parse_ranges::<u64>("11-22")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(11 + 22));
+        let input = parse_ranges::<u64>("11-22,95-115")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(11 + 22 + 99 + 111));
+        let input = parse_ranges::<u64>("95-115")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(99 + 111));
+        let input = parse_ranges::<u64>("998-1012")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(999 + 1010));
+        let input = parse_ranges::<u64>("1188511880-1188511890")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(1_188_511_885));
+        let input = parse_ranges::<u64>("222220-222224")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(222_222));
+        let input = parse_ranges::<u64>("1698522-1698528")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(0));
+        let input = parse_ranges::<u64>("446443-446449")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(446_446));
+        let input = parse_ranges::<u64>("565653-565659")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(565_656));
+        let input = parse_ranges::<u64>("824824821-824824827")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(824_824_824));
+        let input = parse_ranges::<u64>("2121212118-2121212124")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(2_121_212_121));
+        let input = parse_ranges::<u64>(
+            "11-22,\
+             95-115,\
+             998-1012,\
+             1188511880-1188511890,\
+             222220-222224,\
+             1698522-1698528,\
+             446443-446449,\
+             38593856-38593862,\
+             565653-565659,\
+             824824821-824824827,\
+             2121212118-2121212124",
+        )?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(4_174_379_265));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_parse_ranges() {
+        let result = parse_ranges::<u64>("11-22,95-115,\n");
+        assert_eq!(
+            result,
+            Err(ParseError::new(1, 14, "ID range `first-last`", ""))
+        );
+        let result = parse_ranges::<u64>("11-22,95_115");
+        assert_eq!(
+            result,
+            Err(ParseError::new(1, 7, "ID range `first-last`", "95_115"))
+        );
+        let result = parse_ranges::<u64>("11-22,95-1x5");
+        assert_eq!(result, Err(ParseError::new(1, 10, "last ID", "1x5")));
+        let result = parse_ranges_lenient::<u64>("11-22,95-1x5,998-1012");
+        assert_eq!(result, vec![(11, 22), (998, 1012)]);
     }
 
     #[test]
-    fn example_solve_part_2() {
-        let input = vec!["11-22"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 11 + 22);
-        let input = vec!["11-22", "95-115"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 11 + 22 + 99 + 111);
-        let input = vec!["95-115"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 99 + 111);
-        let input = vec!["998-1012"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 999 + 1010);
-        let input = vec!["1188511880-1188511890"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 1188511885);
-        let input = vec!["222220-222224"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 222222);
-        let input = vec!["1698522-1698528"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 0);
-        let input = vec!["446443-446449"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 446446);
-        let input = vec!["565653-565659"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 565656);
-        let input = vec!["824824821-824824827"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 824824824);
-        let input = vec!["2121212118-2121212124"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 2121212121);
-        let input = vec![
-            "11-22",
-            "95-115",
-            "998-1012",
-            "1188511880-1188511890",
-            "222220-222224",
-            "1698522-1698528",
-            "446443-446449",
-            "38593856-38593862",
-            "565653-565659",
-            "824824821-824824827",
-            "2121212118-2121212124",
-        ];
-        let result = solve_part_2(input);
-        assert_eq!(result, 4174379265);
+    fn overflow_solve_part_1() -> Result<(), ParseError> {
+        let input = parse_ranges::<u64>("900000000900000000-999999999999999999")?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Err(SolveError::Overflow));
+        let input = parse_ranges::<u128>("900000000900000000-999999999999999999")?;
+        let result = solve_part_1(&input);
+        assert!(result.is_ok());
+
+        Ok(())
+    }
+
+    #[test]
+    fn overlapping_ranges() -> Result<(), ParseError> {
+        let input = parse_ranges::<u64>("95-115,11-22,11-22,100-1012")?;
+        let result = solve_part_2(&input);
+        assert_eq!(
+            result,
+            Ok(11 + 22 + 99 + 111 + 222 + 333 + 444 + 555 + 666 + 777 + 888 + 999 + 1010)
+        );
+        let input = parse_ranges::<u64>("11-22,115-95")?;
+        let result = solve_part_2(&input);
+        assert_eq!(
+            result,
+            Err(SolveError::ReversedRange {
+                index: 1,
+                first: 115,
+                last: 95
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn thirty_digit_ids() -> Result<(), ParseError> {
+        let range = "123456789012345123456789012345-123456789012345123456789012345";
+        let result = parse_ranges::<u64>(range);
+        assert!(result.is_err());
+        let input = parse_ranges::<u128>(range)?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Ok(123_456_789_012_345_123_456_789_012_345));
+        let input =
+            parse_ranges::<u128>("111111111111111111111111111110-111111111111111111111111111112")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(111_111_111_111_111_111_111_111_111_111));
+
+        Ok(())
+    }
+
+    #[test]
+    fn golden_inputs() -> Result<(), GoldenError> {
+        let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        check_inputs::<Day2>(&inputs)?;
+
+        Ok(())
     }
 }
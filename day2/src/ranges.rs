@@ -0,0 +1,136 @@
+use std::fmt::{self, Debug, Display};
+use std::str::FromStr;
+
+use crate::SolveError;
+
+/// Integer type the IDs are read into: `u64`, or `u128` for IDs of up to 38
+/// digits.
+pub trait Id: Copy + Ord + Debug + Display + FromStr + Into<u128> + TryFrom<u128> {}
+
+impl Id for u64 {}
+
+impl Id for u128 {}
+
+/// What [`normalize_ranges`] does with a range whose first ID is above its last.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Reversed {
+    /// Fails with [`SolveError::ReversedRange`].
+    Reject,
+    /// Swaps its bounds and reports it in [`Normalized::swapped`].
+    Swap,
+}
+
+/// A reversed range that [`normalize_ranges`] read with its bounds swapped.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Swapped<T> {
+    /// Index of the range in the input.
+    pub index: usize,
+    pub first: T,
+    pub last: T,
+}
+
+impl<T: Id> Display for Swapped<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Self { index, first, last } = self;
+        write!(
+            f,
+            "reversed range {index} `{first}-{last}` read as `{last}-{first}`"
+        )
+    }
+}
+
+/// ID ranges sorted and merged, so that no ID is counted twice.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Normalized<T> {
+    /// Disjoint ranges in increasing order, none following another directly.
+    pub ranges: Vec<(T, T)>,
+    /// Input ranges that were reversed and got swapped, in input order.
+    pub swapped: Vec<Swapped<T>>,
+}
+
+/// Sorts the ranges and merges those that overlap or touch.
+///
+/// # Errors
+///
+/// Returns [`SolveError::ReversedRange`] for the first reversed range when
+/// `reversed` is [`Reversed::Reject`].
+pub fn normalize_ranges<T: Id>(
+    ranges: &[(T, T)],
+    reversed: Reversed,
+) -> Result<Normalized<T>, SolveError> {
+    let mut swapped = vec![];
+    let mut sorted = Vec::with_capacity(ranges.len());
+    for (index, &(first, last)) in ranges.iter().enumerate() {
+        if first <= last {
+            sorted.push((first, last));
+        } else if reversed == Reversed::Swap {
+            swapped.push(Swapped { index, first, last });
+            sorted.push((last, first));
+        } else {
+            return Err(SolveError::ReversedRange {
+                index,
+                first: first.into(),
+                last: last.into(),
+            });
+        }
+    }
+    sorted.sort_unstable();
+
+    let mut merged: Vec<(T, T)> = Vec::with_capacity(sorted.len());
+    for (first, last) in sorted {
+        match merged.last_mut() {
+            Some(previous) if first.into() <= previous.1.into().saturating_add(1) => {
+                previous.1 = previous.1.max(last);
+            }
+            _ => merged.push((first, last)),
+        }
+    }
+
+    Ok(Normalized {
+        ranges: merged,
+        swapped,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn merge_ranges() -> Result<(), SolveError> {
+        let ranges: [(u64, u64); 5] = [(95, 115), (11, 22), (20, 30), (31, 40), (100, 101)];
+        let result = normalize_ranges(&ranges, Reversed::Reject)?;
+        assert_eq!(result.ranges, vec![(11, 40), (95, 115)]);
+        assert_eq!(result.swapped, vec![]);
+        let ranges = [(0, 5), (6, u128::MAX), (u128::MAX, 10)];
+        let result = normalize_ranges(&ranges, Reversed::Swap)?;
+        assert_eq!(result.ranges, vec![(0, u128::MAX)]);
+        assert_eq!(
+            result.swapped,
+            vec![Swapped {
+                index: 2,
+                first: u128::MAX,
+                last: 10
+            }]
+        );
+        assert_eq!(
+            result.swapped[0].to_string(),
+            format!("reversed range 2 `{0}-10` read as `10-{0}`", u128::MAX)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn reject_reversed_ranges() {
+        let result = normalize_ranges(&[(11u64, 22), (115, 95)], Reversed::Reject);
+        assert_eq!(
+            result,
+            Err(SolveError::ReversedRange {
+                index: 1,
+                first: 115,
+                last: 95
+            })
+        );
+    }
+}
@@ -0,0 +1,127 @@
+//! Brute force solvers visiting every ID, kept as an oracle for the fast ones.
+
+use crate::{Id, Reversed, SolveError, normalize_ranges};
+
+/// Sums the IDs made of a digit block repeated twice, counting the IDs of
+/// overlapping ranges once.
+///
+/// # Errors
+///
+/// Returns [`SolveError::ReversedRange`] for a range whose first ID is above its
+/// last and [`SolveError::Overflow`] when the sum does not fit in a `T`.
+pub fn solve_part_1<T: Id>(ranges: &[(T, T)]) -> Result<T, SolveError> {
+    let mut result: u128 = 0;
+    for (first, last) in normalize_ranges(ranges, Reversed::Reject)?.ranges {
+        for id in first.into()..=last.into() {
+            let id_str = id.to_string();
+            let id_len = id_str.len();
+            if id_len % 2 == 0 {
+                let a = &id_str[..(id_len / 2)];
+                let b = &id_str[(id_len / 2)..];
+                if a == b {
+                    result = id.checked_add(result).ok_or(SolveError::Overflow)?;
+                }
+            }
+        }
+    }
+
+    T::try_from(result).map_err(|_| SolveError::Overflow)
+}
+
+fn is_magic(s: &str, steps: usize) -> bool {
+    let mut value = None;
+    for part in (0..s.len())
+        .step_by(steps)
+        .map(|i| &s[i..(i + steps).min(s.len())])
+    {
+        match value {
+            Some(value) => {
+                if value != part {
+                    return false;
+                }
+            }
+            None => value = Some(part),
+        }
+    }
+    true
+}
+
+/// Sums the IDs made of a digit block repeated at least twice, counting the IDs
+/// of overlapping ranges once.
+///
+/// # Errors
+///
+/// Returns [`SolveError::ReversedRange`] for a range whose first ID is above its
+/// last and [`SolveError::Overflow`] when the sum does not fit in a `T`.
+pub fn solve_part_2<T: Id>(ranges: &[(T, T)]) -> Result<T, SolveError> {
+    let mut result: u128 = 0;
+    for (first, last) in normalize_ranges(ranges, Reversed::Reject)?.ranges {
+        for id in first.into()..=last.into() {
+            let id_str = id.to_string();
+            let id_len = id_str.len();
+            for steps in 1..id_len {
+                if id_len % steps == 0 && is_magic(&id_str, steps) {
+                    result = id.checked_add(result).ok_or(SolveError::Overflow)?;
+                    break;
+                }
+            }
+        }
+    }
+
+    T::try_from(result).map_err(|_| SolveError::Overflow)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{Counterexample, differential};
+
+    use super::*;
+    use crate::generate::{Options, generate};
+    use crate::parse_ranges;
+
+    type Case = Counterexample<(u64, u64), (Result<u64, SolveError>, Result<u64, SolveError>)>;
+
+    fn simplify(range: &(u64, u64)) -> Vec<(u64, u64)> {
+        let (first, last) = *range;
+        if first >= last {
+            return vec![];
+        }
+        let middle = first + (last - first) / 2;
+        vec![
+            (first, middle),
+            (middle + 1, last),
+            (first + 1, last),
+            (first, last - 1),
+        ]
+    }
+
+    fn check(options: &Options) -> Result<(), Box<Case>> {
+        differential(
+            0..50,
+            |seed| {
+                parse_ranges::<u64>(&generate(seed, options))
+                    .unwrap_or_else(|err| panic!("seed {seed} generated an invalid input: {err}"))
+            },
+            simplify,
+            |ranges| (solve_part_1(ranges), solve_part_2(ranges)),
+            |ranges| (crate::solve_part_1(ranges), crate::solve_part_2(ranges)),
+        )
+        .map_err(Box::new)
+    }
+
+    #[test]
+    fn fast_matches_reference() -> Result<(), Box<Case>> {
+        check(&Options {
+            ranges: 4,
+            max_digits: 8,
+            max_len: 3000,
+        })?;
+        check(&Options {
+            ranges: 4,
+            max_digits: 19,
+            max_len: 300,
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,486 @@
+use std::error::Error;
+use std::fmt;
+use std::marker::PhantomData;
+
+use crate::Id;
+
+/// How many times a block must repeat for an ID to be invalid. An ID counts as
+/// soon as it can be written with one of the allowed counts: `1111` is `11`
+/// twice as well as `1` four times.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Repetition {
+    Exactly(u32),
+    AtLeast(u32),
+    AnyOf(Vec<u32>),
+}
+
+impl Repetition {
+    fn allows(&self, count: u32) -> bool {
+        match self {
+            Self::Exactly(exactly) => count == *exactly,
+            Self::AtLeast(at_least) => count >= *at_least,
+            Self::AnyOf(counts) => counts.contains(&count),
+        }
+    }
+
+    fn counts(&self) -> &[u32] {
+        match self {
+            Self::Exactly(count) | Self::AtLeast(count) => std::slice::from_ref(count),
+            Self::AnyOf(counts) => counts,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum QueryError {
+    /// Only the bases from 2 to 36 have digits.
+    Base(u32),
+    /// A block must repeat at least twice.
+    Repetition(u32),
+}
+
+impl fmt::Display for QueryError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Base(base) => write!(f, "base {base} is not between 2 and 36"),
+            Self::Repetition(count) => {
+                write!(f, "a block repeated {count} time(s) is not a repetition")
+            }
+        }
+    }
+}
+
+impl Error for QueryError {}
+
+/// An invalid ID and why: it is `block` written `times` times in a row.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct InvalidId<T> {
+    pub id: T,
+    pub block: T,
+    pub times: u32,
+}
+
+/// The invalid IDs looked for: made of a block of digits in `base` repeated an
+/// allowed number of times.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Query {
+    repetition: Repetition,
+    base: u32,
+}
+
+/// What [`Query::measure`] adds up over the invalid IDs.
+#[derive(Debug, Clone, Copy)]
+enum Measure {
+    Count,
+    Sum,
+}
+
+/// Möbius function of `n`.
+const fn mobius(mut n: u32) -> i8 {
+    let mut result = 1;
+    let mut factor = 2;
+    while factor * factor <= n {
+        if n.is_multiple_of(factor) {
+            n /= factor;
+            if n.is_multiple_of(factor) {
+                return 0;
+            }
+            result = -result;
+        }
+        factor += 1;
+    }
+    if n > 1 { -result } else { result }
+}
+
+fn divisors(n: u32) -> impl Iterator<Item = u32> {
+    (1..=n).filter(move |d| n.is_multiple_of(*d))
+}
+
+impl Query {
+    /// # Errors
+    ///
+    /// Returns a [`QueryError`] when `base` is not between 2 and 36 or when
+    /// `repetition` allows a block repeated less than twice.
+    pub fn new(repetition: Repetition, base: u32) -> Result<Self, QueryError> {
+        if !(2..=36).contains(&base) {
+            return Err(QueryError::Base(base));
+        }
+        if let Some(&count) = repetition.counts().iter().find(|&&count| count < 2) {
+            return Err(QueryError::Repetition(count));
+        }
+
+        Ok(Self { repetition, base })
+    }
+
+    /// Decimal IDs made of a block repeated exactly twice, part 1 of the puzzle.
+    #[must_use]
+    pub const fn twice() -> Self {
+        Self {
+            repetition: Repetition::Exactly(2),
+            base: 10,
+        }
+    }
+
+    /// Decimal IDs made of a block repeated twice or more, part 2.
+    #[must_use]
+    pub const fn at_least_twice() -> Self {
+        Self {
+            repetition: Repetition::AtLeast(2),
+            base: 10,
+        }
+    }
+
+    #[must_use]
+    pub const fn repetition(&self) -> &Repetition {
+        &self.repetition
+    }
+
+    #[must_use]
+    pub const fn base(&self) -> u32 {
+        self.base
+    }
+
+    /// Number of digits of `id`.
+    fn digits(&self, id: u128) -> u32 {
+        id.checked_ilog(u128::from(self.base))
+            .map_or(1, |log| log + 1)
+    }
+
+    /// Lengths of the blocks whose allowed repetitions make `len` digits long IDs.
+    fn blocks(&self, len: u32) -> Vec<u32> {
+        divisors(len)
+            .filter(|&block| block < len && self.repetition.allows(len / block))
+            .collect()
+    }
+
+    /// Blocks of `block` digits that, repeated to `len` digits, give an ID of
+    /// `first..=last`: the lowest and highest such blocks and the multiplier
+    /// repeating them, `None` when there is none.
+    fn block_range(
+        &self,
+        first: u128,
+        last: u128,
+        len: u32,
+        block: u32,
+    ) -> Option<(u128, u128, u128)> {
+        let base = u128::from(self.base);
+        let shift = base.pow(block);
+        let mut multiplier: u128 = 1;
+        for _ in 1..len / block {
+            multiplier = multiplier.checked_mul(shift)?.checked_add(1)?;
+        }
+        let low = first.max(base.checked_pow(len - 1)?);
+        let high = last.min(base.checked_pow(len).map_or(u128::MAX, |power| power - 1));
+        let low_block = low.div_ceil(multiplier).max(base.pow(block - 1));
+        let high_block = (high / multiplier).min(shift - 1);
+        if low > high || low_block > high_block {
+            return None;
+        }
+
+        Some((low_block, high_block, multiplier))
+    }
+
+    /// Count or sum of the IDs of `first..=last` that have `len` digits and are
+    /// made of a block of `block` digits repeated, `None` when it overflows.
+    fn repeated(
+        &self,
+        first: u128,
+        last: u128,
+        len: u32,
+        block: u32,
+        measure: Measure,
+    ) -> Option<u128> {
+        let Some((low_block, high_block, multiplier)) = self.block_range(first, last, len, block)
+        else {
+            return Some(0);
+        };
+
+        let count = high_block - low_block + 1;
+        match measure {
+            Measure::Count => Some(count),
+            Measure::Sum => {
+                (low_block.checked_add(high_block)?.checked_mul(count)? / 2).checked_mul(multiplier)
+            }
+        }
+    }
+
+    /// Count or sum of the invalid IDs of `first..=last` having `len` digits.
+    /// With `S(d)` the IDs made of a block of `d` digits, an ID whose shortest
+    /// block has `p` digits is in every `S(d)` with `p | d`, so by Möbius
+    /// inversion the IDs whose shortest block has `p` digits are
+    /// `Σ μ(p / d) S(d)` over the divisors `d` of `p`. Those are invalid when an
+    /// allowed count divides `len / p`, the most times their block repeats.
+    fn measure_len(&self, first: u128, last: u128, len: u32, measure: Measure) -> Option<u128> {
+        let mut repeated = vec![0; len as usize + 1];
+        for block in divisors(len).filter(|&block| block < len) {
+            repeated[block as usize] = self.repeated(first, last, len, block, measure)?;
+        }
+
+        let mut result: u128 = 0;
+        for shortest in divisors(len).filter(|&shortest| shortest < len) {
+            let most = len / shortest;
+            if !divisors(most).any(|times| times >= 2 && self.repetition.allows(times)) {
+                continue;
+            }
+            // Every `S(d)` is within `S(p)`, so is the exact result: wrapping
+            // around cannot lose anything.
+            let mut shortest_result: u128 = 0;
+            for block in divisors(shortest) {
+                let term = repeated[block as usize];
+                shortest_result = match mobius(shortest / block) {
+                    1 => shortest_result.wrapping_add(term),
+                    -1 => shortest_result.wrapping_sub(term),
+                    _ => shortest_result,
+                };
+            }
+            result = result.checked_add(shortest_result)?;
+        }
+
+        Some(result)
+    }
+
+    fn measure(&self, first: u128, last: u128, measure: Measure) -> Option<u128> {
+        let mut result: u128 = 0;
+        for len in self.digits(first)..=self.digits(last) {
+            result = result.checked_add(self.measure_len(first, last, len, measure)?)?;
+        }
+
+        Some(result)
+    }
+
+    /// Sum of the invalid IDs of `first..=last` in closed form, in time bound by
+    /// the number of digits rather than the width of the range. `None` when it
+    /// overflows.
+    #[must_use]
+    pub fn sum<T: Id>(&self, first: T, last: T) -> Option<u128> {
+        self.measure(first.into(), last.into(), Measure::Sum)
+    }
+
+    /// Number of invalid IDs of `first..=last`, in closed form like [`Query::sum`].
+    #[must_use]
+    pub fn count<T: Id>(&self, first: T, last: T) -> Option<u128> {
+        self.measure(first.into(), last.into(), Measure::Count)
+    }
+
+    /// Why `id` is invalid: its shortest block whose repetition count is
+    /// allowed, `None` when it is valid.
+    #[must_use]
+    pub fn invalid_id<T: Id>(&self, id: T) -> Option<InvalidId<T>> {
+        let value = id.into();
+        let len = self.digits(value);
+        self.blocks(len).into_iter().find_map(|block| {
+            let (low_block, high_block, multiplier) = self.block_range(value, value, len, block)?;
+            // Both bounds are the quotient, when `value` is a multiple.
+            if low_block != high_block || low_block * multiplier != value {
+                return None;
+            }
+
+            Some(InvalidId {
+                id,
+                block: T::try_from(low_block).ok()?,
+                times: len / block,
+            })
+        })
+    }
+
+    /// Invalid IDs of `first..=last` in increasing order, generated from their
+    /// blocks instead of testing every ID, so in time proportional to the number
+    /// of matches.
+    #[must_use]
+    pub fn ids<T: Id>(&self, first: T, last: T) -> RepeatedIds<T> {
+        RepeatedIds {
+            query: self.clone(),
+            first: first.into(),
+            last: last.into(),
+            len: self.digits(first.into()),
+            cursors: vec![],
+            id: PhantomData,
+        }
+    }
+}
+
+/// Next block of one length and the last one, with the multiplier repeating them.
+#[derive(Debug, Clone)]
+struct Cursor {
+    block: u128,
+    last: u128,
+    multiplier: u128,
+}
+
+impl Cursor {
+    const fn id(&self) -> u128 {
+        self.block * self.multiplier
+    }
+}
+
+/// Iterator over the invalid IDs of a range in increasing order, built by
+/// [`Query::ids`]. Merges the IDs made of each block length, so holds only one
+/// cursor per block length whatever the width of the range.
+#[derive(Debug, Clone)]
+pub struct RepeatedIds<T> {
+    query: Query,
+    first: u128,
+    last: u128,
+    /// Length of the IDs the cursors do not cover yet.
+    len: u32,
+    cursors: Vec<Cursor>,
+    id: PhantomData<T>,
+}
+
+impl<T: Id> Iterator for RepeatedIds<T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.cursors.is_empty() {
+            if self.len > self.query.digits(self.last) {
+                return None;
+            }
+            self.cursors = self
+                .query
+                .blocks(self.len)
+                .into_iter()
+                .filter_map(|block| {
+                    self.query
+                        .block_range(self.first, self.last, self.len, block)
+                })
+                .map(|(block, last, multiplier)| Cursor {
+                    block,
+                    last,
+                    multiplier,
+                })
+                .collect();
+            self.len += 1;
+        }
+
+        // The same ID may be made of blocks of several lengths, like 1111.
+        let id = self.cursors.iter().map(Cursor::id).min()?;
+        for cursor in &mut self.cursors {
+            if cursor.id() == id {
+                cursor.block += 1;
+            }
+        }
+        self.cursors.retain(|cursor| cursor.block <= cursor.last);
+
+        // Within `first..=last`, always fits in a `T`.
+        T::try_from(id).ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Rng;
+
+    use super::*;
+
+    fn to_digits(mut id: u64, base: u64) -> Vec<u64> {
+        let mut digits = vec![];
+        loop {
+            digits.push(id % base);
+            id /= base;
+            if id == 0 {
+                return digits;
+            }
+        }
+    }
+
+    fn is_invalid(id: u64, query: &Query) -> bool {
+        let digits = to_digits(id, u64::from(query.base()));
+        let len = digits.len();
+        (2..=len)
+            .filter(|times| len.is_multiple_of(*times))
+            .any(|times| {
+                let block = len / times;
+                query
+                    .repetition()
+                    .allows(u32::try_from(times).unwrap_or_default())
+                    && digits.chunks(block).all(|chunk| chunk == &digits[..block])
+            })
+    }
+
+    #[test]
+    fn example_repeated_ids() {
+        let result: Vec<u64> = Query::at_least_twice().ids(95, 1012).collect();
+        assert_eq!(
+            result,
+            vec![99, 111, 222, 333, 444, 555, 666, 777, 888, 999, 1010]
+        );
+        let result: Vec<u64> = Query::twice().ids(95, 1012).collect();
+        assert_eq!(result, vec![99, 1010]);
+        let result: Vec<u64> = Query::twice().ids(2, 1).collect();
+        assert_eq!(result, vec![]);
+    }
+
+    #[test]
+    fn repetitions_and_bases() -> Result<(), QueryError> {
+        let query = Query::new(Repetition::Exactly(3), 10)?;
+        let result: Vec<u64> = query.ids(100, 1_000_000).collect();
+        assert_eq!(result.first(), Some(&111));
+        assert!(result.contains(&121_212));
+        assert!(!result.contains(&123_123));
+        assert_eq!(query.count(100u64, 1_000_000), Some(9 + 90));
+
+        let query = Query::new(Repetition::AtLeast(2), 2)?;
+        let result: Vec<u64> = query.ids(0, 16).collect();
+        assert_eq!(result, vec![3, 7, 10, 15]);
+        let query = Query::new(Repetition::AnyOf(vec![2, 5]), 16)?;
+        assert_eq!(query.ids(0u64, 0x1_0000).next(), Some(0x11));
+
+        assert_eq!(
+            Query::new(Repetition::AtLeast(2), 37),
+            Err(QueryError::Base(37))
+        );
+        assert_eq!(
+            Query::new(Repetition::AnyOf(vec![3, 1]), 10),
+            Err(QueryError::Repetition(1))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn generated_matches_filtered() -> Result<(), QueryError> {
+        let mut rng = Rng::new(3);
+        for _ in 0..200 {
+            let base = u32::try_from(rng.range(2..=36)).unwrap_or(10);
+            let repetition = match rng.range(0..=2) {
+                0 => Repetition::Exactly(u32::try_from(rng.range(2..=4)).unwrap_or(2)),
+                1 => Repetition::AtLeast(u32::try_from(rng.range(2..=4)).unwrap_or(2)),
+                _ => Repetition::AnyOf(vec![2, 3]),
+            };
+            let query = Query::new(repetition, base)?;
+            let first = rng.range(0..=200_000);
+            let last = first + rng.range(0..=5000);
+
+            let result: Vec<u64> = query.ids(first, last).collect();
+            let expected: Vec<u64> = (first..=last)
+                .filter(|&id| is_invalid(id, &query))
+                .collect();
+            assert_eq!(result, expected, "{query:?} {first}-{last}");
+            let sum = result.iter().map(|&id| u128::from(id)).sum();
+            assert_eq!(query.sum(first, last), Some(sum));
+            assert_eq!(query.count(first, last), Some(result.len() as u128));
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    fn huge_range() -> Result<(), QueryError> {
+        let result: Vec<u64> = Query::twice()
+            .ids(18_446_744_000_000_000_000, u64::MAX)
+            .collect();
+        assert_eq!(result.len(), 8);
+        assert_eq!(result.last(), Some(&18_446_744_071_844_674_407));
+        let result = Query::at_least_twice().ids(1, u64::MAX).nth(8);
+        assert_eq!(result, Some(99));
+        let query = Query::new(Repetition::AtLeast(2), 2)?;
+        assert_eq!(query.ids(u64::MAX, u64::MAX).next(), Some(u64::MAX));
+        let first = 10u128.pow(29);
+        let result = Query::twice().ids(first, u128::MAX).next();
+        assert_eq!(result, Some(100_000_000_000_000_100_000_000_000_000));
+        let result = Query::twice().count(first, 10u128.pow(30) - 1);
+        assert_eq!(result, Some(9 * 10u128.pow(14)));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,227 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt::Write;
+use std::io;
+use std::process::ExitCode;
+
+use common::{Source, sources};
+
+use crate::{Id, InvalidId, Query, Reversed, normalize_ranges, parse_ranges};
+
+/// The invalid IDs of one input range, in increasing order.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct RangeReport<T> {
+    pub range: (T, T),
+    pub invalid: Vec<InvalidId<T>>,
+}
+
+/// Lists the invalid IDs of every range as given, overlapping ones included, so
+/// that each flagged ID can be traced back to the range it comes from. A
+/// reversed range has no ID.
+#[must_use]
+pub fn report<T: Id>(ranges: &[(T, T)], query: &Query) -> Vec<RangeReport<T>> {
+    ranges
+        .iter()
+        .map(|&(first, last)| RangeReport {
+            range: (first, last),
+            invalid: query
+                .ids(first, last)
+                .filter_map(|id| query.invalid_id(id))
+                .collect(),
+        })
+        .collect()
+}
+
+/// Quotes a CSV field holding a separator, a quote or a line break.
+fn csv_field(field: &str) -> Cow<'_, str> {
+    if field.contains([',', '"', '\n', '\r']) {
+        Cow::Owned(format!("\"{}\"", field.replace('"', "\"\"")))
+    } else {
+        Cow::Borrowed(field)
+    }
+}
+
+/// Writes the reports of every input as a single CSV document, one line per
+/// invalid ID after a header line, each line naming the input it comes from.
+#[must_use]
+pub fn report_csv<T: Id>(inputs: &[(String, Vec<RangeReport<T>>)]) -> String {
+    let mut csv = String::from("source,range,id,block,times");
+    for (source, reports) in inputs {
+        let source = csv_field(source);
+        for report in reports {
+            let (first, last) = report.range;
+            for invalid in &report.invalid {
+                let _ = write!(
+                    csv,
+                    "\n{source},{first}-{last},{},{},{}",
+                    invalid.id, invalid.block, invalid.times
+                );
+            }
+        }
+    }
+
+    csv
+}
+
+/// Reports the invalid IDs of one input, swapping the reversed ranges around
+/// with a warning.
+fn report_input(
+    input: &str,
+    query: &Query,
+    err: &mut impl io::Write,
+) -> Result<Vec<RangeReport<u128>>, Box<dyn Error>> {
+    let mut ranges = parse_ranges::<u128>(input)?;
+    for swapped in normalize_ranges(&ranges, Reversed::Swap)?.swapped {
+        writeln!(err, "warning: {swapped}")?;
+        ranges[swapped.index] = (swapped.last, swapped.first);
+    }
+
+    Ok(report(&ranges, query))
+}
+
+fn report_sources(
+    sources: &[Source],
+    query: &Query,
+    out: &mut impl io::Write,
+    err: &mut impl io::Write,
+) -> ExitCode {
+    let mut status = ExitCode::SUCCESS;
+    let mut inputs = vec![];
+    for source in sources {
+        match source
+            .read()
+            .map_err(Into::into)
+            .and_then(|input| report_input(&input, query, err))
+        {
+            Ok(reports) => inputs.push((source.to_string(), reports)),
+            Err(error) => {
+                status = ExitCode::FAILURE;
+                if writeln!(err, "{source}: {error}").is_err() {
+                    return ExitCode::FAILURE;
+                }
+            }
+        }
+    }
+    if writeln!(out, "{}", report_csv(&inputs)).is_err() {
+        return ExitCode::FAILURE;
+    }
+
+    status
+}
+
+/// Body of the `--csv` mode of the binaries: reports the invalid IDs of every
+/// input named by `args` as one CSV document on stdout. An input that cannot
+/// be read or parsed is reported on stderr and left out.
+pub fn report_inputs(args: impl IntoIterator<Item = OsString>, query: &Query) -> ExitCode {
+    report_sources(
+        &sources(args.into_iter()),
+        query,
+        &mut io::stdout(),
+        &mut io::stderr(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{QueryError, Repetition};
+
+    #[test]
+    fn explain_invalid_ids() -> Result<(), QueryError> {
+        let query = Query::at_least_twice();
+        let result = query.invalid_id(1111u64);
+        assert_eq!(
+            result,
+            Some(InvalidId {
+                id: 1111,
+                block: 1,
+                times: 4,
+            })
+        );
+        assert_eq!(query.invalid_id(1112u64), None);
+        let result = Query::twice().invalid_id(1111u64);
+        assert_eq!(
+            result,
+            Some(InvalidId {
+                id: 1111,
+                block: 11,
+                times: 2,
+            })
+        );
+        let query = Query::new(Repetition::Exactly(3), 10)?;
+        assert_eq!(query.invalid_id(1111u64), None);
+        let query = Query::new(Repetition::AtLeast(2), 2)?;
+        let result = query.invalid_id(10u128);
+        assert_eq!(
+            result,
+            Some(InvalidId {
+                id: 10,
+                block: 2,
+                times: 2,
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn csv_report() {
+        let reports = report(
+            &[(95u64, 115), (11, 22), (30, 20)],
+            &Query::at_least_twice(),
+        );
+        assert_eq!(reports[2].invalid, vec![]);
+        assert_eq!(
+            report_csv(&[("a,b.txt".to_string(), reports)]),
+            "source,range,id,block,times\n\
+             \"a,b.txt\",95-115,99,9,2\n\
+             \"a,b.txt\",95-115,111,1,3\n\
+             \"a,b.txt\",11-22,11,1,2\n\
+             \"a,b.txt\",11-22,22,2,2"
+        );
+    }
+
+    #[test]
+    fn csv_report_inputs() {
+        let dir = std::env::temp_dir().join(format!("day2-report-{}", std::process::id()));
+        let _ = std::fs::create_dir_all(&dir);
+        let first = dir.join("first.txt");
+        let second = dir.join("second.txt");
+        let invalid = dir.join("invalid.txt");
+        let _ = std::fs::write(&first, "11-22");
+        let _ = std::fs::write(&second, "115-95\n");
+        let _ = std::fs::write(&invalid, "11-x");
+        let sources = [
+            Source::File(first.clone()),
+            Source::File(invalid.clone()),
+            Source::File(second.clone()),
+        ];
+
+        let mut out = vec![];
+        let mut err = vec![];
+        let result = report_sources(&sources, &Query::twice(), &mut out, &mut err);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(result, ExitCode::FAILURE);
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            format!(
+                "source,range,id,block,times\n\
+                 {first},11-22,11,1,2\n\
+                 {first},11-22,22,2,2\n\
+                 {second},95-115,99,9,2\n",
+                first = first.display(),
+                second = second.display()
+            )
+        );
+        let err = String::from_utf8_lossy(&err);
+        let lines: Vec<&str> = err.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert!(lines[0].starts_with(&format!("{}: ", invalid.display())));
+        assert_eq!(
+            lines[1],
+            "warning: reversed range 0 `115-95` read as `95-115`"
+        );
+    }
+}
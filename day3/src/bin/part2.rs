@@ -1,25 +1,26 @@
-use std::io::{self, Read};
+use std::process::ExitCode;
 
-use day3::solve_part_2;
+use common::{solve_reader_args, take_flag};
+use day3::{highlight_inputs, try_solve_reader, try_solve_reader_parallel};
 
-fn parse_str(str: &str) -> Vec<String> {
-    let mut parsed_str = vec![];
-
-    for word in str.split_whitespace() {
-        parsed_str.push(word.to_string());
+/// Streams the banks, solving them across threads with `--parallel`, or with
+/// `--highlight` shows the batteries to turn on.
+fn main() -> ExitCode {
+    let mut args = std::env::args_os().skip(1).collect();
+    let highlight = take_flag(&mut args, "--highlight");
+    let parallel = take_flag(&mut args, "--parallel");
+    if highlight && parallel {
+        eprintln!("`--highlight` cannot be combined with `--parallel`");
+        return ExitCode::FAILURE;
     }
-    parsed_str
-}
-
-fn get_input() -> Vec<String> {
-    let mut buf = String::new();
-    let _ = io::stdin().read_to_string(&mut buf);
-
-    parse_str(&buf)
-}
-
-fn main() {
-    let input = get_input();
-    let result = solve_part_2(input.iter().map(std::string::String::as_str).collect());
-    println!("{result}");
+    if highlight {
+        return highlight_inputs(args, 12);
+    }
+    solve_reader_args(args, |reader| {
+        Ok(if parallel {
+            try_solve_reader_parallel(reader, 12)?
+        } else {
+            try_solve_reader(reader, 12)?
+        })
+    })
 }
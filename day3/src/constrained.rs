@@ -0,0 +1,302 @@
+use crate::Selection;
+
+/// Cost of turning on each battery of a bank and how much may be spent in all.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Budget {
+    /// Cost per battery index, the batteries past the end costing nothing.
+    pub costs: Vec<u64>,
+    pub budget: u64,
+}
+
+/// Rules restricting which batteries of a bank may be turned on together.
+#[derive(Debug, PartialEq, Eq, Clone, Default)]
+pub struct Constraints {
+    /// Least number of batteries left off between two turned on.
+    pub min_gap: usize,
+    /// Indices of the batteries that cannot be turned on.
+    pub unavailable: Vec<usize>,
+    /// No limit on the cost when `None`.
+    pub budget: Option<Budget>,
+}
+
+/// Bank with its constraints resolved per battery.
+struct Problem<'a> {
+    bank: &'a [u64],
+    size: usize,
+    /// Distance from a battery turned on to the next one that may be.
+    step: usize,
+    available: Vec<bool>,
+    costs: Vec<u64>,
+    budget: u64,
+}
+
+impl Problem<'_> {
+    /// Index where the batteries following one turned on at `index` start.
+    fn next(&self, index: usize) -> usize {
+        index.saturating_add(self.step).min(self.bank.len())
+    }
+
+    /// Cheapest cost of turning on batteries from `index` onwards, for each
+    /// count of batteries, `u64::MAX` when there are not enough of them.
+    /// `matches(battery, count)` tells whether the battery may be the first of
+    /// `count` ones still to turn on.
+    fn cheapest(&self, matches: impl Fn(u64, usize) -> bool) -> Vec<Vec<u64>> {
+        let len = self.bank.len();
+        let mut cheapest = vec![vec![u64::MAX; self.size + 1]; len + 1];
+        for index in (0..=len).rev() {
+            cheapest[index][0] = 0;
+            if index == len {
+                continue;
+            }
+            for count in 1..=self.size {
+                let skip = cheapest[index + 1][count];
+                let take = if self.available[index] && matches(self.bank[index], count) {
+                    self.costs[index].saturating_add(cheapest[self.next(index)][count - 1])
+                } else {
+                    u64::MAX
+                };
+                cheapest[index][count] = skip.min(take);
+            }
+        }
+
+        cheapest
+    }
+
+    /// Largest digits to read, decided one after the other: the next digit is
+    /// the largest one that some cheapest way of reading the digits so far can
+    /// be followed by while staying within the budget.
+    fn best_digits(&self) -> Option<Vec<u64>> {
+        let len = self.bank.len();
+        let cheapest = self.cheapest(|_, _| true);
+        if cheapest[0][self.size] > self.budget {
+            return None;
+        }
+
+        let mut values: Vec<u64> = self.bank.to_vec();
+        values.sort_unstable_by(|a, b| b.cmp(a));
+        values.dedup();
+
+        let mut digits = Vec::with_capacity(self.size);
+        // Cheapest cost of the digits so far when the next battery may be at
+        // `index`, for each index.
+        let mut reach: Vec<u64> = vec![0; len + 1];
+        for left in (1..=self.size).rev() {
+            let cost = |index: usize| {
+                reach[index]
+                    .saturating_add(self.costs[index])
+                    .saturating_add(cheapest[self.next(index)][left - 1])
+            };
+            let digit = values.iter().copied().find(|&digit| {
+                (0..len).any(|index| {
+                    self.available[index] && self.bank[index] == digit && cost(index) <= self.budget
+                })
+            })?;
+            digits.push(digit);
+
+            let mut next_reach = vec![u64::MAX; len + 1];
+            for (index, &reached) in reach.iter().enumerate().take(len) {
+                if self.available[index] && self.bank[index] == digit {
+                    let next = self.next(index);
+                    let cost = reached.saturating_add(self.costs[index]);
+                    next_reach[next] = next_reach[next].min(cost);
+                }
+            }
+            for index in 1..=len {
+                next_reach[index] = next_reach[index].min(next_reach[index - 1]);
+            }
+            reach = next_reach;
+        }
+
+        Some(digits)
+    }
+
+    /// Lexicographically first indices reading `digits` within the budget,
+    /// taking each time the first battery the rest can still follow.
+    fn first_indices(&self, digits: &[u64]) -> Option<Vec<usize>> {
+        let cheapest = self.cheapest(|battery, left| battery == digits[self.size - left]);
+
+        let mut indices = Vec::with_capacity(self.size);
+        let (mut start, mut spent) = (0, 0u64);
+        for (position, &digit) in digits.iter().enumerate() {
+            let left = self.size - position;
+            let index = (start..self.bank.len()).find(|&index| {
+                self.available[index]
+                    && self.bank[index] == digit
+                    && spent
+                        .saturating_add(self.costs[index])
+                        .saturating_add(cheapest[self.next(index)][left - 1])
+                        <= self.budget
+            })?;
+            indices.push(index);
+            spent = spent.saturating_add(self.costs[index]);
+            start = self.next(index);
+        }
+
+        Some(indices)
+    }
+}
+
+/// Same as [`select_batteries`](crate::select_batteries) under `constraints`,
+/// by dynamic programming over the cheapest way to turn on the remaining
+/// batteries: the largest joltage first, then the lexicographically first
+/// indices making it. Takes `O(d·n·k)` time for `d` distinct joltage ratings,
+/// `n` batteries and `k` turned on. `None` when no choice of `size` batteries
+/// meets the constraints.
+#[must_use]
+pub fn select_constrained(
+    bank: &[u64],
+    size: usize,
+    constraints: &Constraints,
+) -> Option<Selection> {
+    let mut available = vec![true; bank.len()];
+    for &index in &constraints.unavailable {
+        if let Some(available) = available.get_mut(index) {
+            *available = false;
+        }
+    }
+    let (costs, budget) = match &constraints.budget {
+        Some(budget) => (
+            (0..bank.len())
+                .map(|index| budget.costs.get(index).copied().unwrap_or_default())
+                .collect(),
+            // `u64::MAX` stands for no way at all.
+            budget.budget.min(u64::MAX - 1),
+        ),
+        None => (vec![0; bank.len()], 0),
+    };
+    let problem = Problem {
+        bank,
+        size,
+        step: constraints.min_gap.saturating_add(1),
+        available,
+        costs,
+        budget,
+    };
+
+    let digits = problem.best_digits()?;
+    let indices = problem.first_indices(&digits)?;
+
+    Some(Selection::new(bank, indices))
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Rng;
+
+    use super::*;
+    use crate::select_batteries;
+
+    /// Tries every choice of `size` batteries in lexicographic order, keeping
+    /// the first one meeting the constraints and making the largest joltage.
+    fn brute_select(bank: &[u64], size: usize, constraints: &Constraints) -> Option<Vec<usize>> {
+        let valid = |indices: &[usize]| {
+            let gaps = indices
+                .windows(2)
+                .all(|pair| pair[1] - pair[0] > constraints.min_gap);
+            let available = indices
+                .iter()
+                .all(|index| !constraints.unavailable.contains(index));
+            let cost = constraints.budget.as_ref().is_none_or(|budget| {
+                let cost: u64 = indices
+                    .iter()
+                    .map(|&index| budget.costs.get(index).copied().unwrap_or_default())
+                    .sum();
+                cost <= budget.budget
+            });
+            gaps && available && cost
+        };
+
+        let mut best: Option<(Vec<u64>, Vec<usize>)> = None;
+        let mut indices: Vec<usize> = (0..size).collect();
+        loop {
+            let digits: Vec<u64> = indices.iter().map(|&index| bank[index]).collect();
+            if valid(&indices) && best.as_ref().is_none_or(|(best, _)| digits > *best) {
+                best = Some((digits, indices.clone()));
+            }
+            let Some(position) = (0..size)
+                .rev()
+                .find(|&i| indices[i] < bank.len() - size + i)
+            else {
+                return best.map(|(_, indices)| indices);
+            };
+            indices[position] += 1;
+            for i in position + 1..size {
+                indices[i] = indices[i - 1] + 1;
+            }
+        }
+    }
+
+    #[test]
+    fn example_constraints() {
+        let bank = [8, 1, 8, 1, 8, 1, 9, 1, 1, 1, 1, 2, 1, 1, 1];
+        let select = |constraints| {
+            select_constrained(&bank, 2, &constraints).map(|selection| selection.indices)
+        };
+        assert_eq!(select(Constraints::default()), Some(vec![6, 11]));
+        assert_eq!(
+            select(Constraints {
+                min_gap: 5,
+                ..Constraints::default()
+            }),
+            Some(vec![6, 12])
+        );
+        assert_eq!(
+            select(Constraints {
+                unavailable: vec![6],
+                ..Constraints::default()
+            }),
+            Some(vec![0, 2])
+        );
+        let mut costs = vec![1; bank.len()];
+        costs[6] = 5;
+        assert_eq!(
+            select(Constraints {
+                budget: Some(Budget { costs, budget: 5 }),
+                ..Constraints::default()
+            }),
+            Some(vec![0, 2])
+        );
+        assert_eq!(
+            select(Constraints {
+                min_gap: 14,
+                ..Constraints::default()
+            }),
+            None
+        );
+    }
+
+    #[test]
+    fn constrained_matches_brute_force() {
+        let mut rng = Rng::new(22);
+        for _ in 0..500 {
+            let len = rng.index(10) + 1;
+            let bank: Vec<u64> = (0..len).map(|_| rng.range(1..=3)).collect();
+            let size = rng.index(len) + 1;
+            let constraints = Constraints {
+                min_gap: rng.index(3),
+                unavailable: (0..len).filter(|_| rng.chance(20)).collect(),
+                budget: rng.chance(70).then(|| Budget {
+                    costs: (0..len).map(|_| rng.range(0..=4)).collect(),
+                    budget: rng.range(0..=10),
+                }),
+            };
+
+            let result = select_constrained(&bank, size, &constraints);
+            let indices = result.as_ref().map(|selection| selection.indices.clone());
+            let expected = brute_select(&bank, size, &constraints);
+            assert_eq!(indices, expected, "{bank:?} {size} {constraints:?}");
+        }
+    }
+
+    #[test]
+    fn unconstrained_matches_stack() {
+        let mut rng = Rng::new(23);
+        for _ in 0..100 {
+            let len = rng.index(60) + 1;
+            let bank: Vec<u64> = (0..len).map(|_| rng.range(1..=9)).collect();
+            let size = rng.index(len + 1);
+            let result = select_constrained(&bank, size, &Constraints::default());
+            assert_eq!(result, select_batteries(&bank, size), "{bank:?} {size}");
+        }
+    }
+}
@@ -0,0 +1,56 @@
+use common::Rng;
+
+/// Size knobs of [`generate`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub banks: usize,
+    /// Batteries per bank.
+    pub len: usize,
+    /// Highest joltage rating, lower it to get many ties.
+    pub max_digit: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            banks: 200,
+            len: 100,
+            max_digit: 9,
+        }
+    }
+}
+
+/// Generates one bank of batteries per line, one joltage digit per battery.
+#[must_use]
+pub fn generate(seed: u64, options: &Options) -> String {
+    let mut rng = Rng::new(seed);
+    let max_digit = options.max_digit.clamp(1, 9);
+    let mut input = String::new();
+    for _ in 0..options.banks {
+        for _ in 0..options.len {
+            input.push_str(&rng.range(1..=max_digit).to_string());
+        }
+        input.push('\n');
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{GeneratorError, check_generator};
+
+    use super::*;
+    use crate::Day3;
+
+    #[test]
+    fn generated_input_parses() -> Result<(), GeneratorError> {
+        let options = Options::default();
+        for input in check_generator::<Day3>(|seed| generate(seed, &options))? {
+            assert_eq!(input.len(), options.banks);
+            assert!(input.iter().all(|bank| bank.len() == options.len));
+        }
+
+        Ok(())
+    }
+}
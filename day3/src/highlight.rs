@@ -0,0 +1,172 @@
+use std::error::Error;
+use std::ffi::OsString;
+use std::fmt::Write;
+use std::io::{self, IsTerminal};
+use std::process::ExitCode;
+
+use common::{Source, sources};
+
+use crate::{SolveError, parse_banks, select_banks};
+
+/// How [`highlight_banks`] marks the batteries to turn on.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Highlight {
+    /// In bold green, for a terminal.
+    Color,
+    /// Between brackets, for a file or a pipe.
+    Brackets,
+}
+
+/// Each bank on its own line with the batteries to turn on marked as
+/// `highlight` says, followed by its joltage, see [`select_banks`].
+///
+/// # Errors
+///
+/// Returns [`SolveError::BankTooShort`] when a bank has fewer than `size`
+/// batteries.
+pub fn highlight_banks(
+    banks: &[Vec<u64>],
+    size: usize,
+    highlight: Highlight,
+) -> Result<String, SolveError> {
+    let mut lines = String::new();
+    for (bank, selection) in banks.iter().zip(select_banks(banks, size)?) {
+        let mut chosen = selection.indices.iter().peekable();
+        for (index, battery) in bank.iter().enumerate() {
+            if chosen.next_if_eq(&&index).is_none() {
+                let _ = write!(lines, "{battery}");
+            } else if highlight == Highlight::Color {
+                let _ = write!(lines, "\x1b[1;32m{battery}\x1b[0m");
+            } else {
+                let _ = write!(lines, "[{battery}]");
+            }
+        }
+        let _ = writeln!(lines, " {}", selection.joltage);
+    }
+    lines.pop();
+
+    Ok(lines)
+}
+
+fn highlight_sources(
+    sources: &[Source],
+    size: usize,
+    highlight: Highlight,
+    out: &mut impl io::Write,
+    err: &mut impl io::Write,
+) -> ExitCode {
+    let mut status = ExitCode::SUCCESS;
+    let mut separator = "";
+    for source in sources {
+        let lines: Result<String, Box<dyn Error>> = source
+            .read()
+            .map_err(Into::into)
+            .and_then(|input| Ok(highlight_banks(&parse_banks(&input)?, size, highlight)?));
+        let written = match lines {
+            Ok(lines) if sources.len() == 1 => writeln!(out, "{lines}"),
+            Ok(lines) => {
+                let written = writeln!(out, "{separator}==> {source} <==\n{lines}");
+                separator = "\n";
+                written
+            }
+            Err(error) => {
+                status = ExitCode::FAILURE;
+                writeln!(err, "{source}: {error}")
+            }
+        };
+        if written.is_err() {
+            return ExitCode::FAILURE;
+        }
+    }
+
+    status
+}
+
+/// Body of the `--highlight` mode of the binaries: shows the batteries turned
+/// on in the banks of every input named by `args`, under a `==> input <==`
+/// header when there are several. The batteries are colored on a terminal and
+/// put between brackets otherwise.
+pub fn highlight_inputs(args: impl IntoIterator<Item = OsString>, size: usize) -> ExitCode {
+    let highlight = if io::stdout().is_terminal() {
+        Highlight::Color
+    } else {
+        Highlight::Brackets
+    };
+    highlight_sources(
+        &sources(args.into_iter()),
+        size,
+        highlight,
+        &mut io::stdout(),
+        &mut io::stderr(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use common::ParseError;
+
+    use super::*;
+
+    #[test]
+    fn highlight_selection() -> Result<(), ParseError> {
+        let input = parse_banks("818181911112111\n9119")?;
+        let result = highlight_banks(&input, 2, Highlight::Color);
+        assert_eq!(
+            result,
+            Ok("818181\x1b[1;32m9\x1b[0m1111\x1b[1;32m2\x1b[0m111 92\n\
+                \x1b[1;32m9\x1b[0m11\x1b[1;32m9\x1b[0m 99"
+                .to_string())
+        );
+        let result = highlight_banks(&input, 2, Highlight::Brackets);
+        assert_eq!(
+            result,
+            Ok("818181[9]1111[2]111 92\n[9]11[9] 99".to_string())
+        );
+        let result = highlight_banks(&input, 5, Highlight::Brackets);
+        assert_eq!(
+            result,
+            Err(SolveError::BankTooShort {
+                bank: 1,
+                len: 4,
+                size: 5
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn highlight_several_sources() {
+        let dir = std::env::temp_dir().join(format!("day3-highlight-{}", std::process::id()));
+        let _ = std::fs::create_dir_all(&dir);
+        let first = dir.join("first.txt");
+        let second = dir.join("second.txt");
+        let invalid = dir.join("invalid.txt");
+        let _ = std::fs::write(&first, "8119\n191");
+        let _ = std::fs::write(&second, "12\n");
+        let _ = std::fs::write(&invalid, "1x");
+        let sources = [
+            Source::File(first.clone()),
+            Source::File(invalid.clone()),
+            Source::File(second.clone()),
+        ];
+
+        let mut out = vec![];
+        let mut err = vec![];
+        let result = highlight_sources(&sources, 2, Highlight::Brackets, &mut out, &mut err);
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(result, ExitCode::FAILURE);
+        assert_eq!(
+            String::from_utf8_lossy(&out),
+            format!(
+                "==> {} <==\n[8]11[9] 89\n1[9][1] 91\n\n==> {} <==\n[1][2] 12\n",
+                first.display(),
+                second.display()
+            )
+        );
+        let err = String::from_utf8_lossy(&err);
+        assert!(err.starts_with(&format!("{}: ", invalid.display())));
+        assert_eq!(err.lines().count(), 1);
+    }
+}
@@ -0,0 +1,242 @@
+use std::cmp::Ordering;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::Add;
+
+/// A joltage of any number of digits, most significant first, as read off the
+/// batteries turned on. Joltages compare by value, whatever their leading zeros.
+#[derive(Debug, Clone, Default)]
+pub struct Joltage {
+    digits: Vec<u64>,
+}
+
+impl Joltage {
+    #[must_use]
+    pub fn digits(&self) -> &[u64] {
+        &self.digits
+    }
+
+    /// The digits without the leading zeros.
+    fn significant(&self) -> &[u64] {
+        let start = self
+            .digits
+            .iter()
+            .position(|&digit| digit != 0)
+            .unwrap_or(self.digits.len());
+        &self.digits[start..]
+    }
+
+    /// The joltage as a number, `None` when it does not fit in a `u64`.
+    #[must_use]
+    pub fn to_u64(&self) -> Option<u64> {
+        self.digits.iter().try_fold(0u64, |result, &digit| {
+            result.checked_mul(10)?.checked_add(digit)
+        })
+    }
+}
+
+impl PartialEq for Joltage {
+    fn eq(&self, other: &Self) -> bool {
+        self.significant() == other.significant()
+    }
+}
+
+impl Eq for Joltage {}
+
+impl Ord for Joltage {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (digits, other) = (self.significant(), other.significant());
+        digits
+            .len()
+            .cmp(&other.len())
+            .then_with(|| digits.cmp(other))
+    }
+}
+
+impl PartialOrd for Joltage {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl fmt::Display for Joltage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.significant() {
+            [] => write!(f, "0"),
+            digits => digits.iter().try_for_each(|digit| write!(f, "{digit}")),
+        }
+    }
+}
+
+impl Add for Joltage {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        let (long, short) = if self.digits.len() >= other.digits.len() {
+            (self.digits, other.digits)
+        } else {
+            (other.digits, self.digits)
+        };
+
+        let mut digits = Vec::with_capacity(long.len() + 1);
+        let mut carry = 0;
+        let mut short = short.iter().rev();
+        for &digit in long.iter().rev() {
+            let sum = digit + short.next().copied().unwrap_or_default() + carry;
+            digits.push(sum % 10);
+            carry = sum / 10;
+        }
+        if carry > 0 {
+            digits.push(carry);
+        }
+        digits.reverse();
+
+        Self { digits }
+    }
+}
+
+impl Sum for Joltage {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), Add::add)
+    }
+}
+
+/// Batteries turned on in a bank and the joltage they make.
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Selection {
+    /// Indices of the batteries in the bank, increasing.
+    pub indices: Vec<usize>,
+    pub joltage: Joltage,
+}
+
+impl Selection {
+    /// Reads the joltage of the batteries of `bank` at `indices`.
+    pub(crate) fn new(bank: &[u64], indices: Vec<usize>) -> Self {
+        let digits = indices.iter().map(|&index| bank[index]).collect();
+        Self {
+            indices,
+            joltage: Joltage { digits },
+        }
+    }
+}
+
+/// Turns on the `size` batteries of `bank` making the largest joltage, in a
+/// single pass: each battery pops the smaller ones before it off a stack for as
+/// long as enough batteries remain to fill the stack back up. Equal batteries
+/// are never popped, so among the choices making the largest joltage this is
+/// the lexicographically first one. `None` when the bank holds fewer than
+/// `size` batteries.
+#[must_use]
+pub fn select_batteries(bank: &[u64], size: usize) -> Option<Selection> {
+    if bank.len() < size {
+        return None;
+    }
+
+    let mut indices: Vec<usize> = Vec::with_capacity(size);
+    for (index, &battery) in bank.iter().enumerate() {
+        let remaining = bank.len() - index;
+        while let Some(&last) = indices.last() {
+            if bank[last] >= battery || indices.len() + remaining <= size {
+                break;
+            }
+            indices.pop();
+        }
+        if indices.len() < size {
+            indices.push(index);
+        }
+    }
+
+    Some(Selection::new(bank, indices))
+}
+
+/// Largest joltage made of `size` batteries of `bank` kept in order, see
+/// [`select_batteries`].
+#[must_use]
+pub fn max_joltage(bank: &[u64], size: usize) -> Option<Joltage> {
+    select_batteries(bank, size).map(|selection| selection.joltage)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::Rng;
+
+    use super::*;
+
+    /// Tries every choice of `size` batteries in lexicographic order, keeping
+    /// the first one making the largest joltage.
+    fn brute_select(bank: &[u64], size: usize) -> Vec<usize> {
+        let mut best: Option<(Vec<u64>, Vec<usize>)> = None;
+        let mut indices: Vec<usize> = (0..size).collect();
+        loop {
+            let digits: Vec<u64> = indices.iter().map(|&index| bank[index]).collect();
+            if best.as_ref().is_none_or(|(best, _)| digits > *best) {
+                best = Some((digits, indices.clone()));
+            }
+            // Next choice: moves right the last index that still can.
+            let Some(position) = (0..size)
+                .rev()
+                .find(|&i| indices[i] < bank.len() - size + i)
+            else {
+                return best.map(|(_, indices)| indices).unwrap_or_default();
+            };
+            indices[position] += 1;
+            for i in position + 1..size {
+                indices[i] = indices[i - 1] + 1;
+            }
+        }
+    }
+
+    #[test]
+    fn lexicographically_first_selection() {
+        let result = select_batteries(&[9, 1, 9, 9], 2);
+        assert_eq!(result.map(|selection| selection.indices), Some(vec![0, 2]));
+
+        let mut rng = Rng::new(21);
+        for _ in 0..300 {
+            let len = rng.index(10) + 1;
+            let bank: Vec<u64> = (0..len).map(|_| rng.range(1..=3)).collect();
+            let size = rng.index(len) + 1;
+            let result = select_batteries(&bank, size).map(|selection| selection.indices);
+            assert_eq!(result, Some(brute_select(&bank, size)), "{bank:?} {size}");
+        }
+    }
+
+    #[test]
+    fn huge_joltage() {
+        let bank: Vec<u64> = (0..1000).map(|index| index % 9 + 1).collect();
+        let result = max_joltage(&bank, 500);
+        let joltage = result.unwrap_or_default();
+        assert_eq!(joltage.digits().len(), 500);
+        assert_eq!(joltage.to_u64(), None);
+        assert!(joltage.to_string().starts_with("99999"));
+        assert_eq!(max_joltage(&bank, 1001), None);
+        assert_eq!(
+            max_joltage(&bank, 0).map(|joltage| joltage.to_string()),
+            Some("0".to_string())
+        );
+    }
+
+    #[test]
+    fn add_joltages() {
+        let bank = [9; 21];
+        let result: Joltage = [max_joltage(&bank, 21), max_joltage(&[0, 1], 2)]
+            .into_iter()
+            .flatten()
+            .sum();
+        assert_eq!(result.to_string(), "1000000000000000000000");
+        assert_eq!(result.to_u64(), None);
+    }
+
+    #[test]
+    fn compare_joltages() {
+        let joltage = |digits: &[u64]| Joltage {
+            digits: digits.to_vec(),
+        };
+        assert_eq!(joltage(&[0, 1]), joltage(&[1]));
+        assert_eq!(joltage(&[0, 0]), Joltage::default());
+        assert!(joltage(&[0, 9]) < joltage(&[1, 0]));
+        assert!(joltage(&[0, 0, 1, 2]) > joltage(&[9]));
+        assert_eq!(joltage(&[0, 1]).cmp(&joltage(&[1])), Ordering::Equal);
+        assert_ne!(joltage(&[1, 0]), joltage(&[1]));
+    }
+}
@@ -1,119 +1,318 @@
-fn solve_n(bank: &[u64], size: usize) -> u64 {
-    let mut result = 0;
-    let mut base_index = 0;
-    for j in 0..size {
-        let bank = &bank[base_index..(bank.len() - (size - 1 - j))];
-        let mut index_max = 0;
-        let mut batterie_max = 0;
-        for (index, batterie) in bank.iter().enumerate() {
-            if batterie_max < *batterie {
-                index_max = index;
-                batterie_max = *batterie;
-            }
+mod constrained;
+pub mod generate;
+mod highlight;
+mod joltage;
+pub mod reference;
+mod stream;
+
+use std::error::Error;
+use std::fmt;
+
+use common::{ParseError, Solution, Token, words};
+
+pub use constrained::{Budget, Constraints, select_constrained};
+pub use highlight::{Highlight, highlight_banks, highlight_inputs};
+pub use joltage::{Joltage, Selection, max_joltage, select_batteries};
+pub use stream::{StreamError, try_solve_reader, try_solve_reader_parallel};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveError {
+    /// The bank at index `bank` holds fewer batteries than have to be turned on.
+    BankTooShort {
+        bank: usize,
+        len: usize,
+        size: usize,
+    },
+    /// The total joltage does not fit in a `u64`.
+    Overflow,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::BankTooShort { bank, len, size } => write!(
+                f,
+                "bank {bank} has {len} batteries but {size} have to be turned on"
+            ),
+            Self::Overflow => write!(f, "the total joltage overflows a u64"),
         }
-        result = result * 10 + batterie_max;
-        base_index += index_max + 1;
     }
-    result
 }
 
-#[must_use]
-pub fn solve_part_1(input: Vec<&str>) -> u64 {
-    let mut result = 0;
+impl Error for SolveError {}
 
-    for bank in input {
-        let mut bank_u64 = vec![];
-        for batterie in bank.chars().filter_map(|c| c.to_digit(10).map(u64::from)) {
-            bank_u64.push(batterie);
+fn parse_bank(bank: Token) -> Result<Vec<u64>, ParseError> {
+    let mut bank_u64 = vec![];
+    for (batterie, token) in bank.chars() {
+        match batterie.to_digit(10) {
+            Some(batterie) => bank_u64.push(u64::from(batterie)),
+            None => return Err(token.error("battery joltage digit")),
         }
-        result += solve_n(&bank_u64, 2);
     }
-    result
+    Ok(bank_u64)
+}
+
+/// Parses the banks of battery joltage digits, one per line, the banks sharing
+/// a line separated by whitespace.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on the first character that is not a digit.
+pub fn parse_banks(input: &str) -> Result<Vec<Vec<u64>>, ParseError> {
+    words(input).map(parse_bank).collect()
 }
 
+/// Same as [`parse_banks`] but silently skips the characters that are not digits.
 #[must_use]
-pub fn solve_part_2(input: Vec<&str>) -> u64 {
-    let mut result = 0;
+pub fn parse_banks_lenient(input: &str) -> Vec<Vec<u64>> {
+    let mut banks = vec![];
 
-    for bank in input {
+    for bank in input.split_whitespace() {
         let mut bank_u64 = vec![];
         for batterie in bank.chars().filter_map(|c| c.to_digit(10).map(u64::from)) {
             bank_u64.push(batterie);
         }
-        result += solve_n(&bank_u64, 12);
+        banks.push(bank_u64);
+    }
+    banks
+}
+
+/// Sums the largest joltage of each bank, turning on `size` batteries per bank,
+/// with as many digits as it takes.
+///
+/// # Errors
+///
+/// Returns [`SolveError::BankTooShort`] when a bank has fewer than `size`
+/// batteries.
+pub fn total_joltage(banks: &[Vec<u64>], size: usize) -> Result<Joltage, SolveError> {
+    per_bank(banks, size, max_joltage).sum()
+}
+
+/// Runs `solve` on each bank, failing on the banks it finds too short.
+fn per_bank<'a, T>(
+    banks: &'a [Vec<u64>],
+    size: usize,
+    solve: impl Fn(&[u64], usize) -> Option<T> + 'a,
+) -> impl Iterator<Item = Result<T, SolveError>> + 'a {
+    banks.iter().enumerate().map(move |(index, bank)| {
+        solve(bank, size).ok_or(SolveError::BankTooShort {
+            bank: index,
+            len: bank.len(),
+            size,
+        })
+    })
+}
+
+/// Batteries to turn on in each bank to get its largest joltage with `size`
+/// batteries, see [`select_batteries`].
+///
+/// # Errors
+///
+/// Returns [`SolveError::BankTooShort`] when a bank has fewer than `size`
+/// batteries.
+pub fn select_banks(banks: &[Vec<u64>], size: usize) -> Result<Vec<Selection>, SolveError> {
+    per_bank(banks, size, select_batteries).collect()
+}
+
+/// Same as [`total_joltage`] as a `u64`.
+///
+/// # Errors
+///
+/// Returns [`SolveError::BankTooShort`] when a bank has fewer than `size`
+/// batteries, or [`SolveError::Overflow`] when the sum does not fit in a `u64`.
+pub fn solve_banks(banks: &[Vec<u64>], size: usize) -> Result<u64, SolveError> {
+    total_joltage(banks, size)?
+        .to_u64()
+        .ok_or(SolveError::Overflow)
+}
+
+/// Sums the largest joltage of each bank, turning on 2 batteries per bank.
+///
+/// # Errors
+///
+/// Returns [`SolveError::BankTooShort`] when a bank has fewer than 2 batteries,
+/// or [`SolveError::Overflow`] when the sum does not fit in a `u64`.
+pub fn solve_part_1(banks: &[Vec<u64>]) -> Result<u64, SolveError> {
+    solve_banks(banks, 2)
+}
+
+/// Sums the largest joltage of each bank, turning on 12 batteries per bank.
+///
+/// # Errors
+///
+/// Returns [`SolveError::BankTooShort`] when a bank has fewer than 12 batteries,
+/// or [`SolveError::Overflow`] when the sum does not fit in a `u64`.
+pub fn solve_part_2(banks: &[Vec<u64>]) -> Result<u64, SolveError> {
+    solve_banks(banks, 12)
+}
+
+pub struct Day3;
+
+impl Solution for Day3 {
+    const DAY: u8 = 3;
+
+    type Input = Vec<Vec<u64>>;
+    type Answer = u64;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_banks(input)
+    }
+
+    fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_1(input)
+    }
+
+    fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_2(input)
     }
-    result
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use common::{GoldenError, check_inputs};
+
     use super::*;
 
     #[test]
     fn custom_solve_part_n() {
         let input = vec![1, 2];
-        let result = solve_n(&input, 1);
-        assert_eq!(result, 2);
+        let result = max_joltage(&input, 1).and_then(|joltage| joltage.to_u64());
+        assert_eq!(result, Some(2));
         let input = vec![1, 2];
-        let result = solve_n(&input, 2);
-        assert_eq!(result, 12);
+        let result = max_joltage(&input, 2).and_then(|joltage| joltage.to_u64());
+        assert_eq!(result, Some(12));
         let input = vec![8, 1, 8, 1, 8, 1, 9, 1, 1, 1, 1, 2, 1, 1, 1];
-        let result = solve_n(&input, 2);
-        assert_eq!(result, 92);
+        let result = max_joltage(&input, 2).and_then(|joltage| joltage.to_u64());
+        assert_eq!(result, Some(92));
     }
 
     #[test]
-    fn example_solve_part_1() {
-        let input = vec!["987654321111111"];
-        let result = solve_part_1(input);
-        assert_eq!(result, 98);
-        let input = vec!["811111111111119"];
-        let result = solve_part_1(input);
-        assert_eq!(result, 89);
-        let input = vec!["234234234234278"];
-        let result = solve_part_1(input);
-        assert_eq!(result, 78);
-        let input = vec!["818181911112111"];
-        let result = solve_part_1(input);
-        assert_eq!(result, 92);
-        let input = vec![
-            "987654321111111",
-            "811111111111119",
-            "234234234234278",
-            "818181911112111",
-        ];
-        let result = solve_part_1(input);
-        assert_eq!(result, 357);
+    fn example_select_banks() -> Result<(), Box<dyn Error>> {
+        let input = parse_banks("818181911112111\n12")?;
+        let result = select_banks(&input, 2)?;
+        assert_eq!(result[0].indices, vec![6, 11]);
+        assert_eq!(result[0].joltage.to_string(), "92");
+        assert_eq!(result[1].indices, vec![0, 1]);
+        let result = select_banks(&input, 3);
+        assert_eq!(
+            result,
+            Err(SolveError::BankTooShort {
+                bank: 1,
+                len: 2,
+                size: 3
+            })
+        );
+
+        Ok(())
     }
 
     #[test]
-    fn custom_solve_part_1() {
-        let input = vec!["111111119"];
-        let result = solve_part_1(input);
-        assert_eq!(result, 19);
+    fn overflow_solve_banks() -> Result<(), ParseError> {
+        let input = parse_banks(&["9".repeat(20), "9".repeat(20)].join("\n"))?;
+        let result = solve_banks(&input, 20);
+        assert_eq!(result, Err(SolveError::Overflow));
+        let result = total_joltage(&input, 20).map(|joltage| joltage.to_string());
+        assert_eq!(result, Ok(format!("1{}8", "9".repeat(19))));
+
+        Ok(())
     }
 
     #[test]
-    fn example_solve_part_2() {
-        let input = vec!["987654321111111"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 987654321111);
-        let input = vec!["811111111111119"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 811111111119);
-        let input = vec!["234234234234278"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 434234234278);
-        let input = vec!["818181911112111"];
-        let result = solve_part_2(input);
-        assert_eq!(result, 888911112111);
-        let input = vec![
-            "987654321111111",
-            "811111111111119",
-            "234234234234278",
-            "818181911112111",
-        ];
-        let result = solve_part_2(input);
-        assert_eq!(result, 3121910778619);
+    fn example_solve_part_1() -> Result<(), ParseError> {
+        let input = parse_banks("987654321111111")?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Ok(98));
+        let input = parse_banks("811111111111119")?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Ok(89));
+        let input = parse_banks("234234234234278")?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Ok(78));
+        let input = parse_banks("818181911112111")?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Ok(92));
+        let input = parse_banks(
+            "987654321111111
+811111111111119
+234234234234278
+818181911112111",
+        )?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Ok(357));
+
+        Ok(())
+    }
+
+    #[test]
+    fn custom_solve_part_1() -> Result<(), ParseError> {
+        let input = parse_banks("111111119")?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Ok(19));
+
+        Ok(())
+    }
+
+    #[test]
+    fn example_solve_part_2() -> Result<(), ParseError> {
+        let input = parse_banks("987654321111111")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(987_654_321_111));
+        let input = parse_banks("811111111111119")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(811_111_111_119));
+        let input = parse_banks("234234234234278")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(434_234_234_278));
+        let input = parse_banks("818181911112111")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(888_911_112_111));
+        let input = parse_banks(
+            "987654321111111
+811111111111119
+234234234234278
+818181911112111",
+        )?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(3_121_910_778_619));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_parse_banks() {
+        let result = parse_banks("987654321111111\n8111111x1111119");
+        assert_eq!(
+            result,
+            Err(ParseError::new(2, 8, "battery joltage digit", "x"))
+        );
+        let result = parse_banks_lenient("98x7");
+        assert_eq!(result, vec![vec![9, 8, 7]]);
+    }
+
+    #[test]
+    fn short_bank_solve_part_2() -> Result<(), ParseError> {
+        let input = parse_banks("987654321111111\n12345")?;
+        let result = solve_part_2(&input);
+        assert_eq!(
+            result,
+            Err(SolveError::BankTooShort {
+                bank: 1,
+                len: 5,
+                size: 12
+            })
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn golden_inputs() -> Result<(), GoldenError> {
+        let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        check_inputs::<Day3>(&inputs)?;
+
+        Ok(())
     }
 }
@@ -0,0 +1,108 @@
+//! Solver rescanning the remaining batteries for every digit, kept as an oracle
+//! for the fast one.
+
+use crate::SolveError;
+
+/// Largest joltage made of `size` batteries of `bank`, at most 19 to fit in a
+/// `u64`, picking each digit as the largest battery leaving enough of them for
+/// the next ones.
+#[must_use]
+pub fn solve_n(bank: &[u64], size: usize) -> u64 {
+    let mut result = 0;
+    let mut base_index = 0;
+    for j in 0..size {
+        let bank = &bank[base_index..(bank.len() - (size - 1 - j))];
+        let mut index_max = 0;
+        let mut batterie_max = 0;
+        for (index, batterie) in bank.iter().enumerate() {
+            if batterie_max < *batterie {
+                index_max = index;
+                batterie_max = *batterie;
+            }
+        }
+        result = result * 10 + batterie_max;
+        base_index += index_max + 1;
+    }
+    result
+}
+
+/// Sums the largest joltage of each bank, turning on `size` batteries per bank.
+///
+/// # Errors
+///
+/// Returns [`SolveError::BankTooShort`] when a bank has fewer than `size`
+/// batteries, or [`SolveError::Overflow`] when the sum does not fit in a `u64`.
+pub fn solve_banks(banks: &[Vec<u64>], size: usize) -> Result<u64, SolveError> {
+    let mut result: u64 = 0;
+
+    for (index, bank) in banks.iter().enumerate() {
+        if bank.len() < size {
+            return Err(SolveError::BankTooShort {
+                bank: index,
+                len: bank.len(),
+                size,
+            });
+        }
+        result = result
+            .checked_add(solve_n(bank, size))
+            .ok_or(SolveError::Overflow)?;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{Counterexample, differential};
+
+    use super::*;
+    use crate::generate::{Options, generate};
+    use crate::parse_banks;
+
+    type Case = Counterexample<Vec<u64>, Vec<Result<u64, SolveError>>>;
+
+    fn simplify(bank: &[u64]) -> Vec<Vec<u64>> {
+        let mut simpler = vec![];
+        for index in 0..bank.len() {
+            let mut shorter = bank.to_vec();
+            shorter.remove(index);
+            simpler.push(shorter);
+            if bank[index] > 1 {
+                let mut lower = bank.to_vec();
+                lower[index] -= 1;
+                simpler.push(lower);
+            }
+        }
+        simpler
+    }
+
+    fn check(options: &Options) -> Result<(), Box<Case>> {
+        let sizes = [1, 2, 7, 12, 19];
+        differential(
+            0..50,
+            |seed| {
+                parse_banks(&generate(seed, options))
+                    .unwrap_or_else(|err| panic!("seed {seed} generated an invalid input: {err}"))
+            },
+            |bank| simplify(bank),
+            |banks| sizes.map(|size| solve_banks(banks, size)).to_vec(),
+            |banks| sizes.map(|size| crate::solve_banks(banks, size)).to_vec(),
+        )
+        .map_err(Box::new)
+    }
+
+    #[test]
+    fn fast_matches_reference() -> Result<(), Box<Case>> {
+        check(&Options {
+            banks: 5,
+            len: 25,
+            max_digit: 3,
+        })?;
+        check(&Options {
+            banks: 5,
+            len: 40,
+            max_digit: 9,
+        })?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,342 @@
+use std::io::BufRead;
+
+use common::ParseError;
+use rayon::prelude::*;
+
+use crate::SolveError;
+
+/// Bytes of input read at once by [`try_solve_reader_parallel`], in whole lines.
+const BATCH_BYTES: usize = 1 << 20;
+
+/// Why streaming the input failed.
+pub type StreamError = common::StreamError<SolveError>;
+
+impl From<SolveError> for StreamError {
+    fn from(err: SolveError) -> Self {
+        Self::Solve(err)
+    }
+}
+
+/// A bank in the bytes of a batch of lines.
+#[derive(Debug, Clone, Copy)]
+struct Bank {
+    /// Index of the bank, blank lines skipped.
+    index: usize,
+    /// Line of the bank, from 1.
+    line: usize,
+    /// Column of the first battery, from 1.
+    column: usize,
+    start: usize,
+    end: usize,
+}
+
+/// Banks of the line at `start..end` of `bytes`, split on spaces and tabs like
+/// [`parse_banks`](crate::parse_banks) does, numbered from `index`.
+fn find_banks(
+    bytes: &[u8],
+    start: usize,
+    end: usize,
+    line: usize,
+    index: usize,
+) -> impl Iterator<Item = Bank> + '_ {
+    let mut offset = start;
+    bytes[start..end]
+        .split(|&byte| byte == b' ' || byte == b'\t')
+        .filter_map(move |word| {
+            let word_start = offset;
+            offset += word.len() + 1;
+            let first = word.iter().position(|byte| !byte.is_ascii_whitespace())?;
+            let last = word.iter().rposition(|byte| !byte.is_ascii_whitespace())?;
+            Some((word_start + first, word_start + last + 1))
+        })
+        .enumerate()
+        .map(move |(number, (bank_start, bank_end))| Bank {
+            index: index + number,
+            line,
+            column: bank_start - start + 1,
+            start: bank_start,
+            end: bank_end,
+        })
+}
+
+/// Largest joltage of the digit bytes of `bank` with `size` batteries turned
+/// on, reusing `stack` so that no bank allocates.
+fn bank_joltage(
+    bytes: &[u8],
+    bank: Bank,
+    size: usize,
+    stack: &mut Vec<u8>,
+) -> Result<u64, StreamError> {
+    let batteries = &bytes[bank.start..bank.end];
+    if let Some(offset) = batteries.iter().position(|byte| !byte.is_ascii_digit()) {
+        let found = String::from_utf8_lossy(&batteries[offset..]);
+        let found: String = found.chars().take(1).collect();
+        return Err(StreamError::Parse(ParseError::new(
+            bank.line,
+            bank.column + offset,
+            "battery joltage digit",
+            found,
+        )));
+    }
+    if batteries.len() < size {
+        return Err(SolveError::BankTooShort {
+            bank: bank.index,
+            len: batteries.len(),
+            size,
+        }
+        .into());
+    }
+
+    stack.clear();
+    for (index, &battery) in batteries.iter().enumerate() {
+        let remaining = batteries.len() - index;
+        while let Some(&last) = stack.last() {
+            if last >= battery || stack.len() + remaining <= size {
+                break;
+            }
+            stack.pop();
+        }
+        if stack.len() < size {
+            stack.push(battery);
+        }
+    }
+
+    stack
+        .iter()
+        .try_fold(0u64, |result, &battery| {
+            result
+                .checked_mul(10)?
+                .checked_add(u64::from(battery - b'0'))
+        })
+        .ok_or(SolveError::Overflow.into())
+}
+
+fn total(sum: u128) -> Result<u64, StreamError> {
+    Ok(u64::try_from(sum).map_err(|_| SolveError::Overflow)?)
+}
+
+/// Sums the largest joltage of each bank of `reader`, the banks separated by
+/// whitespace like [`parse_banks`](crate::parse_banks) reads them, turning on
+/// `size` batteries per bank. Works on the bytes of one line at a time, without
+/// allocating per bank.
+///
+/// # Errors
+///
+/// Returns a [`StreamError`] when reading fails, on the first battery that is
+/// not a digit, for a bank with fewer than `size` batteries and when a joltage
+/// or the sum does not fit in a `u64`.
+pub fn try_solve_reader(mut reader: impl BufRead, size: usize) -> Result<u64, StreamError> {
+    let mut line = vec![];
+    let mut stack = Vec::with_capacity(size);
+    let (mut index, mut number) = (0, 0);
+    let mut sum: u128 = 0;
+    loop {
+        line.clear();
+        if reader.read_until(b'\n', &mut line)? == 0 {
+            return total(sum);
+        }
+        number += 1;
+        for bank in find_banks(&line, 0, line.len(), number, index) {
+            sum += u128::from(bank_joltage(&line, bank, size, &mut stack)?);
+            index += 1;
+        }
+    }
+}
+
+/// Same as [`try_solve_reader`], reading the input in batches of lines whose
+/// banks are solved across threads.
+///
+/// # Errors
+///
+/// Returns the same [`StreamError`] as [`try_solve_reader`] would, the one of
+/// the first line in error.
+pub fn try_solve_reader_parallel(reader: impl BufRead, size: usize) -> Result<u64, StreamError> {
+    solve_batches(reader, size, BATCH_BYTES)
+}
+
+fn solve_batches(
+    mut reader: impl BufRead,
+    size: usize,
+    batch_bytes: usize,
+) -> Result<u64, StreamError> {
+    let mut batch = Vec::with_capacity(batch_bytes);
+    let mut banks = vec![];
+    let (mut index, mut number) = (0, 0);
+    let mut sum: u128 = 0;
+    let mut done = false;
+    while !done {
+        batch.clear();
+        banks.clear();
+        // A read error is only returned once the banks read before it are
+        // solved, in case one of them fails first.
+        let mut read_error = None;
+        while batch.len() < batch_bytes {
+            let start = batch.len();
+            match reader.read_until(b'\n', &mut batch) {
+                Ok(0) => done = true,
+                Ok(_) => {}
+                Err(err) => {
+                    read_error = Some(err);
+                    done = true;
+                }
+            }
+            if done {
+                break;
+            }
+            number += 1;
+            for bank in find_banks(&batch, start, batch.len(), number, index) {
+                banks.push(bank);
+                index += 1;
+            }
+        }
+
+        let batch_sum = banks
+            .par_iter()
+            .map_init(Vec::new, |stack, &bank| {
+                bank_joltage(&batch, bank, size, stack)
+                    .map(u128::from)
+                    .map_err(|err| (bank.line, err))
+            })
+            .reduce(
+                || Ok(0),
+                |a, b| match (a, b) {
+                    (Ok(a), Ok(b)) => Ok(a + b),
+                    (Err(a), Err(b)) => Err(if a.0 <= b.0 { a } else { b }),
+                    (Err(err), Ok(_)) | (Ok(_), Err(err)) => Err(err),
+                },
+            );
+        sum += batch_sum.map_err(|(_, err)| err)?;
+        if let Some(err) = read_error {
+            return Err(err.into());
+        }
+    }
+
+    total(sum)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::error::Error;
+    use std::io::{self, BufReader, Read};
+
+    use super::*;
+    use crate::generate::{Options, generate};
+    use crate::{parse_banks, solve_banks};
+
+    #[test]
+    fn stream_banks() -> Result<(), StreamError> {
+        let input = "987654321111111\r\n811111111111119\n\n  234234234234278\n818181911112111";
+        let result = try_solve_reader(input.as_bytes(), 2)?;
+        assert_eq!(result, 357);
+        let result = try_solve_reader_parallel(input.as_bytes(), 12)?;
+        assert_eq!(result, 3_121_910_778_619);
+        let result = solve_batches(input.as_bytes(), 12, 1)?;
+        assert_eq!(result, 3_121_910_778_619);
+
+        Ok(())
+    }
+
+    #[test]
+    fn banks_on_one_line() -> Result<(), Box<dyn Error>> {
+        let input = "987 123\n\t811111111111119  42\r\n";
+        let expected = solve_banks(&parse_banks(input)?, 2)?;
+        assert_eq!(expected, 98 + 23 + 89 + 42);
+        assert_eq!(try_solve_reader(input.as_bytes(), 2)?, expected);
+        assert_eq!(solve_batches(input.as_bytes(), 2, 1)?, expected);
+        let result = try_solve_reader("12 3x".as_bytes(), 1);
+        assert_eq!(
+            result.map_err(|err| err.to_string()),
+            Err("line 1, column 5: expected battery joltage digit, found `x`".to_string())
+        );
+        let result = try_solve_reader("12 3".as_bytes(), 2);
+        assert!(matches!(
+            result,
+            Err(StreamError::Solve(SolveError::BankTooShort {
+                bank: 1,
+                len: 1,
+                size: 2
+            }))
+        ));
+
+        Ok(())
+    }
+
+    #[test]
+    fn stream_matches_parse() -> Result<(), Box<dyn Error>> {
+        let options = Options {
+            banks: 500,
+            len: 30,
+            max_digit: 4,
+        };
+        for seed in 0..5 {
+            let input = generate(seed, &options);
+            let banks = parse_banks(&input)?;
+            for size in [1, 2, 12] {
+                let expected = solve_banks(&banks, size)?;
+                assert_eq!(try_solve_reader(input.as_bytes(), size)?, expected);
+                assert_eq!(solve_batches(input.as_bytes(), size, 100)?, expected);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Reads like a disk failing after the bytes it was given.
+    struct Failing<'a>(&'a [u8]);
+
+    impl Read for Failing<'_> {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            if self.0.is_empty() {
+                return Err(io::Error::other("disk failure"));
+            }
+            self.0.read(buf)
+        }
+    }
+
+    #[test]
+    fn read_error_after_banks() {
+        let input = "12\n9\n34\n";
+        for result in [
+            try_solve_reader(BufReader::new(Failing(input.as_bytes())), 2),
+            solve_batches(BufReader::new(Failing(input.as_bytes())), 2, 100),
+        ] {
+            assert!(matches!(
+                result,
+                Err(StreamError::Solve(SolveError::BankTooShort { bank: 1, .. }))
+            ));
+        }
+        let result = solve_batches(BufReader::new(Failing(b"12\n34\n")), 2, 100);
+        assert!(matches!(result, Err(StreamError::Io(_))));
+    }
+
+    #[test]
+    fn invalid_stream() {
+        let input = "12\n\n1234\n9x9\n1\n1y";
+        for result in [
+            try_solve_reader(input.as_bytes(), 2),
+            solve_batches(input.as_bytes(), 2, 4),
+            try_solve_reader_parallel(input.as_bytes(), 2),
+        ] {
+            assert_eq!(
+                result.map_err(|err| err.to_string()),
+                Err("line 4, column 2: expected battery joltage digit, found `x`".to_string())
+            );
+        }
+        let result = try_solve_reader_parallel("12\n\n1234\n9".as_bytes(), 2);
+        assert!(matches!(
+            result,
+            Err(StreamError::Solve(SolveError::BankTooShort {
+                bank: 2,
+                len: 1,
+                size: 2
+            }))
+        ));
+        let result = try_solve_reader(&[b'1', 0xff, b'\n'][..], 1);
+        assert!(matches!(result, Err(StreamError::Parse(_))));
+        let result = try_solve_reader("9".repeat(20).as_bytes(), 20);
+        assert!(matches!(
+            result,
+            Err(StreamError::Solve(SolveError::Overflow))
+        ));
+    }
+}
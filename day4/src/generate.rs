@@ -0,0 +1,58 @@
+use common::Rng;
+
+/// Size knobs of [`generate`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub width: usize,
+    pub height: usize,
+    /// Percentage of cells holding a roll of paper.
+    pub density: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            width: 140,
+            height: 140,
+            density: 60,
+        }
+    }
+}
+
+/// Generates a grid of rolls of paper `@` and empty cells `.`.
+#[must_use]
+pub fn generate(seed: u64, options: &Options) -> String {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    for _ in 0..options.height {
+        for _ in 0..options.width {
+            input.push(if rng.chance(options.density) {
+                '@'
+            } else {
+                '.'
+            });
+        }
+        input.push('\n');
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{GeneratorError, check_generator};
+
+    use super::*;
+    use crate::Day4;
+
+    #[test]
+    fn generated_input_parses() -> Result<(), GeneratorError> {
+        let options = Options::default();
+        for input in check_generator::<Day4>(|seed| generate(seed, &options))? {
+            assert_eq!(input.height(), options.height);
+            assert_eq!(input.width(), options.width);
+        }
+
+        Ok(())
+    }
+}
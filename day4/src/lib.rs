@@ -1,10 +1,12 @@
-#![allow(
-    clippy::cast_possible_truncation,
-    clippy::cast_sign_loss,
-    clippy::cast_possible_wrap
-)]
+pub mod generate;
+pub mod reference;
 
-#[derive(Copy, Clone)]
+use std::convert::Infallible;
+use std::fmt;
+
+use common::{Grid, ParseError, Point, Solution, Token};
+
+#[derive(Debug, PartialEq, Eq, Copy, Clone)]
 pub enum Cell {
     Paper,
     Empty,
@@ -19,73 +21,87 @@ const fn parse_cell(c: char) -> Cell {
     }
 }
 
-pub fn parse_grid(str: &str) -> Vec<Vec<Cell>> {
-    let mut grid = vec![];
-
-    for line in str.split_whitespace() {
-        let row = line.chars().map(parse_cell).collect();
-        grid.push(row);
+impl fmt::Display for Cell {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Paper => write!(f, "@"),
+            Self::Empty => write!(f, "."),
+            Self::Other => write!(f, "?"),
+        }
     }
-    grid
 }
 
-fn is_accessible(grid: &[Vec<Cell>], y: usize, x: usize) -> bool {
-    let mut adjacent_papers = 0;
-    for derive in &[
-        (-1, -1),
-        (-1, 0),
-        (-1, 1),
-        (0, -1),
-        (0, 1),
-        (1, -1),
-        (1, 0),
-        (1, 1),
-    ] {
-        let y_len = grid.len();
-        let x_len = grid[y].len();
-        let y = y as i32 + derive.0;
-        let x = x as i32 + derive.1;
-        if y >= 0
-            && y < y_len as i32
-            && x >= 0
-            && x < x_len as i32
-            && matches!(grid[y as usize][x as usize], Cell::Paper)
-        {
-            adjacent_papers += 1;
-        }
-        if adjacent_papers >= 4 {
-            return false;
-        }
+fn parse_known_cell(c: char, token: Token) -> Result<Cell, ParseError> {
+    match parse_cell(c) {
+        Cell::Other => Err(token.error("`@` or `.`")),
+        cell => Ok(cell),
     }
-    true
 }
 
+/// Parses the grid of paper rolls (`@`) and empty spaces (`.`).
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on the first character that is neither `@` nor `.`
+/// and on the first row not as wide as the first one.
+pub fn parse_grid(str: &str) -> Result<Grid<Cell>, ParseError> {
+    Grid::parse(str, parse_known_cell)
+}
+
+/// Same as [`parse_grid`] but keeps the unknown characters as [`Cell::Other`],
+/// padding the short rows with it too.
+#[must_use]
+pub fn parse_grid_lenient(str: &str) -> Grid<Cell> {
+    Grid::parse_lenient(str, parse_cell, Cell::Other)
+}
+
+fn is_accessible(grid: &Grid<Cell>, point: Point) -> bool {
+    grid.neighbors8(point)
+        .filter(|&neighbor| grid[neighbor] == Cell::Paper)
+        .take(4)
+        .count()
+        < 4
+}
+
+/// Counts the rolls of paper that can be accessed by a forklift.
 #[must_use]
-pub fn solve_part_1(grid: &[Vec<Cell>]) -> u64 {
+pub fn solve_part_1(grid: &Grid<Cell>) -> u64 {
     let mut result = 0;
-    for y in 0..grid.len() {
-        for x in 0..grid[y].len() {
-            if matches!(grid[y][x], Cell::Paper) && is_accessible(grid, y, x) {
-                result += 1;
-            }
+    for point in grid.points() {
+        if grid[point] == Cell::Paper && is_accessible(grid, point) {
+            result += 1;
         }
     }
 
     result
 }
 
-#[must_use]
-pub fn solve_part_2(grid: &mut [Vec<Cell>]) -> u64 {
+/// Counts the rolls of paper removed by repeatedly removing the accessible ones.
+/// Keeps the count of rolls around each cell and a worklist of the accessible
+/// rolls, so that removing a roll only updates its neighbors: linear in the size
+/// of the grid.
+pub fn solve_part_2(grid: &mut Grid<Cell>) -> u64 {
+    let mut counts = grid.map(|point, _| {
+        grid.neighbors8(point)
+            .filter(|&neighbor| grid[neighbor] == Cell::Paper)
+            .count()
+    });
+    let mut queue: Vec<Point> = grid
+        .points()
+        .filter(|&point| grid[point] == Cell::Paper && counts[point] < 4)
+        .collect();
+
     let mut result = 0;
-    let mut updated = true;
-    while updated {
-        updated = false;
-        for y in 0..grid.len() {
-            for x in 0..grid[y].len() {
-                if matches!(grid[y][x], Cell::Paper) && is_accessible(grid, y, x) {
-                    result += 1;
-                    grid[y][x] = Cell::Empty;
-                    updated = true;
+    while let Some(point) = queue.pop() {
+        grid[point] = Cell::Empty;
+        result += 1;
+        for neighbor in grid.neighbors8(point) {
+            // A roll is queued when its count drops to 3, unless it started lower
+            // and was queued from the start, so never twice.
+            if grid[neighbor] == Cell::Paper {
+                counts[neighbor] -= 1;
+                if counts[neighbor] == 3 {
+                    queue.push(neighbor);
                 }
             }
         }
@@ -94,12 +110,38 @@ pub fn solve_part_2(grid: &mut [Vec<Cell>]) -> u64 {
     result
 }
 
+pub struct Day4;
+
+impl Solution for Day4 {
+    const DAY: u8 = 4;
+
+    type Input = Grid<Cell>;
+    type Answer = u64;
+    type Error = Infallible;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_grid(input)
+    }
+
+    fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        Ok(solve_part_1(input))
+    }
+
+    fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        Ok(solve_part_2(&mut input.clone()))
+    }
+}
+
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use common::{GoldenError, check_inputs};
+
     use super::*;
 
     #[test]
-    fn example_solve_part_1() {
+    fn example_solve_part_1() -> Result<(), ParseError> {
         let input = "..@@.@@@@.
 @@@.@.@.@@
 @@@@@.@.@@
@@ -110,13 +152,15 @@ mod tests {
 @.@@@.@@@@
 .@@@@@@@@.
 @.@.@@@.@.";
-        let input = parse_grid(input);
+        let input = parse_grid(input)?;
         let result = solve_part_1(&input);
         assert_eq!(result, 13);
+
+        Ok(())
     }
 
     #[test]
-    fn example_solve_part_2() {
+    fn example_solve_part_2() -> Result<(), ParseError> {
         let input = "..@@.@@@@.
 @@@.@.@.@@
 @@@@@.@.@@
@@ -127,8 +171,53 @@ mod tests {
 @.@@@.@@@@
 .@@@@@@@@.
 @.@.@@@.@.";
-        let mut input = parse_grid(input);
+        let mut input = parse_grid(input)?;
         let result = solve_part_2(&mut input);
         assert_eq!(result, 43);
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_parse_grid() {
+        let input = "..@@.
+@@#.@";
+        let result = parse_grid(input);
+        assert!(matches!(
+            result,
+            Err(ParseError {
+                line: 2,
+                column: 3,
+                ..
+            })
+        ));
+        let result = parse_grid_lenient(input);
+        assert_eq!(result[Point::new(2, 1)], Cell::Other);
+    }
+
+    #[test]
+    fn ragged_parse_grid() -> Result<(), ParseError> {
+        let input = "..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@";
+        let result = parse_grid(input);
+        assert_eq!(
+            result,
+            Err(ParseError::new(3, 1, "row of 10 cells", "@@@@@.@"))
+        );
+        let result = parse_grid_lenient(input);
+        assert_eq!(result.to_string().lines().last(), Some("@@@@@.@???"));
+        let input = parse_grid("@@.\n.@@")?;
+        assert_eq!(parse_grid(&input.to_string()), Ok(input));
+
+        Ok(())
+    }
+
+    #[test]
+    fn golden_inputs() -> Result<(), GoldenError> {
+        let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        check_inputs::<Day4>(&inputs)?;
+
+        Ok(())
     }
 }
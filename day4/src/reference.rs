@@ -0,0 +1,87 @@
+//! Solver rescanning the whole grid until no roll can be removed, kept as an
+//! oracle for the worklist one.
+
+use common::Grid;
+
+use crate::{Cell, is_accessible};
+
+/// Counts the rolls of paper removed by repeatedly removing the accessible ones,
+/// one pass over the grid after the other.
+pub fn solve_part_2(grid: &mut Grid<Cell>) -> u64 {
+    let mut result = 0;
+    let mut updated = true;
+    while updated {
+        updated = false;
+        for point in grid.points() {
+            if grid[point] == Cell::Paper && is_accessible(grid, point) {
+                result += 1;
+                grid[point] = Cell::Empty;
+                updated = true;
+            }
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{Counterexample, differential};
+
+    use super::*;
+    use crate::generate::{Options, generate};
+    use crate::parse_grid;
+
+    type Case = Counterexample<Vec<Cell>, Option<(u64, Grid<Cell>)>>;
+
+    /// The same row with one roll of paper less.
+    fn simplify(row: &[Cell]) -> Vec<Vec<Cell>> {
+        let mut simpler = vec![];
+        for (index, &cell) in row.iter().enumerate() {
+            if cell == Cell::Paper {
+                let mut fewer = row.to_vec();
+                fewer[index] = Cell::Empty;
+                simpler.push(fewer);
+            }
+        }
+        simpler
+    }
+
+    /// Solves the grid of `rows`, returning the count and the grid left.
+    fn solve(rows: &[Vec<Cell>], solve: fn(&mut Grid<Cell>) -> u64) -> Option<(u64, Grid<Cell>)> {
+        let mut grid = Grid::new(rows.first().map_or(0, Vec::len), rows.concat())?;
+        let result = solve(&mut grid);
+        Some((result, grid))
+    }
+
+    fn check(options: &Options) -> Result<(), Box<Case>> {
+        differential(
+            0..50,
+            |seed| {
+                let grid = parse_grid(&generate(seed, options))
+                    .unwrap_or_else(|err| panic!("seed {seed} generated an invalid input: {err}"));
+                grid.rows().map(<[Cell]>::to_vec).collect()
+            },
+            |row| simplify(row),
+            |rows| solve(rows, solve_part_2),
+            |rows| solve(rows, crate::solve_part_2),
+        )
+        .map_err(Box::new)
+    }
+
+    #[test]
+    fn worklist_matches_reference() -> Result<(), Box<Case>> {
+        check(&Options {
+            width: 6,
+            height: 6,
+            density: 70,
+        })?;
+        check(&Options {
+            width: 40,
+            height: 30,
+            density: 80,
+        })?;
+
+        Ok(())
+    }
+}
@@ -1,16 +1,15 @@
-use std::io::{self, Read};
+use std::error::Error;
+use std::process::ExitCode;
 
-use day5::{Data, parse_data, solve_part_1};
+use common::solve_inputs;
+use day5::{parse_data, solve_part_1};
 
-fn get_input() -> Data {
-    let mut buf = String::new();
-    let _ = io::stdin().read_to_string(&mut buf);
+fn solve(input: &str) -> Result<u64, Box<dyn Error>> {
+    let input = parse_data(input)?;
 
-    parse_data(&buf)
+    Ok(solve_part_1(&input)?)
 }
 
-fn main() {
-    let input = get_input();
-    let result = solve_part_1(&input);
-    println!("{result}");
+fn main() -> ExitCode {
+    solve_inputs(solve)
 }
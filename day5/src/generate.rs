@@ -0,0 +1,63 @@
+use std::fmt::Write;
+
+use common::Rng;
+
+/// Size knobs of [`generate`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub ranges: usize,
+    pub ids: usize,
+    /// Highest ID, of a range or an ingredient.
+    pub max_id: u64,
+    /// Most IDs in a range.
+    pub max_len: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            ranges: 200,
+            ids: 1000,
+            max_id: 500_000_000_000_000,
+            max_len: 20_000_000_000_000,
+        }
+    }
+}
+
+/// Generates the fresh ID ranges, a blank line, then the available ingredient IDs.
+#[must_use]
+pub fn generate(seed: u64, options: &Options) -> String {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    for _ in 0..options.ranges {
+        let first = rng.range(1..=options.max_id);
+        let len = rng.range(1..=options.max_len.max(1));
+        let last = first.saturating_add(len - 1).min(options.max_id.max(first));
+        let _ = writeln!(input, "{first}-{last}");
+    }
+    input.push('\n');
+    for _ in 0..options.ids {
+        let _ = writeln!(input, "{}", rng.range(1..=options.max_id));
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{GeneratorError, check_generator};
+
+    use super::*;
+    use crate::Day5;
+
+    #[test]
+    fn generated_input_parses() -> Result<(), GeneratorError> {
+        let options = Options::default();
+        for input in check_generator::<Day5>(|seed| generate(seed, &options))? {
+            assert_eq!(input.ranges.len(), options.ranges);
+            assert_eq!(input.ids.len(), options.ids);
+        }
+
+        Ok(())
+    }
+}
@@ -1,3 +1,26 @@
+pub mod generate;
+
+use std::error::Error;
+use std::fmt;
+
+use common::{ParseError, Solution, Token, words};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveError {
+    /// The count of fresh IDs does not fit in a `u64`.
+    Overflow,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "the count of fresh IDs overflows a u64"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
 enum ParsedLine {
     Range((u64, u64)),
     Id(u64),
@@ -9,36 +32,57 @@ pub struct Data {
     ids: Vec<u64>,
 }
 
-fn parse_line(line: &str) -> Option<ParsedLine> {
-    if let Some(range) = line.split_once('-')
-        && let Ok(a) = range.0.parse::<u64>()
-        && let Ok(b) = range.1.parse::<u64>()
-    {
-        Some(ParsedLine::Range((a, b)))
-    } else if let Ok(id) = line.parse::<u64>() {
-        Some(ParsedLine::Id(id))
+fn parse_line(line: Token) -> Result<ParsedLine, ParseError> {
+    if let Some((a, b)) = line.split_once('-') {
+        Ok(ParsedLine::Range((
+            a.parse("first ID")?,
+            b.parse("last ID")?,
+        )))
     } else {
-        None
+        Ok(ParsedLine::Id(line.parse("ingredient ID or ID range")?))
+    }
+}
+
+/// Parses the fresh ID ranges (`3-5`) and the available ingredient IDs (`17`).
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on the first line that is neither an ID range nor an ID.
+pub fn parse_data(input: &str) -> Result<Data, ParseError> {
+    let mut ranges = vec![];
+    let mut ids = vec![];
+
+    for line in words(input) {
+        match parse_line(line)? {
+            ParsedLine::Range(range) => ranges.push(range),
+            ParsedLine::Id(id) => ids.push(id),
+        }
     }
+    Ok(Data { ranges, ids })
 }
 
+/// Same as [`parse_data`] but silently skips the lines that are neither ID ranges nor IDs.
 #[must_use]
-pub fn parse_data(input: &str) -> Data {
+pub fn parse_data_lenient(input: &str) -> Data {
     let mut ranges = vec![];
     let mut ids = vec![];
 
-    for line in input.split_whitespace() {
+    for line in words(input) {
         match parse_line(line) {
-            Some(ParsedLine::Range(range)) => ranges.push(range),
-            Some(ParsedLine::Id(id)) => ids.push(id),
-            None => {}
+            Ok(ParsedLine::Range(range)) => ranges.push(range),
+            Ok(ParsedLine::Id(id)) => ids.push(id),
+            Err(_) => {}
         }
     }
     Data { ranges, ids }
 }
 
-#[must_use]
-pub fn solve_part_1(data: &Data) -> u64 {
+/// Counts the available IDs that are fresh.
+///
+/// # Errors
+///
+/// Never fails for now, the count is bounded by the number of IDs.
+pub fn solve_part_1(data: &Data) -> Result<u64, SolveError> {
     let mut result = 0;
     for id in &data.ids {
         for range in &data.ranges {
@@ -49,30 +93,35 @@ pub fn solve_part_1(data: &Data) -> u64 {
         }
     }
 
-    result
+    Ok(result)
 }
 
+/// Removes from `range` its ends overlapping `other_range`, `None` once nothing is left.
 #[allow(clippy::suspicious_operation_groupings)]
-#[must_use]
-pub fn solve_part_2(data: &Data) -> u64 {
-    let mut result = 0;
+fn trim_range(mut range: (u64, u64), other_range: (u64, u64)) -> Option<(u64, u64)> {
+    if other_range.0 <= range.0 && range.0 <= other_range.1 {
+        range.0 = other_range.1.checked_add(1)?;
+    }
+    if other_range.0 <= range.1 && range.1 <= other_range.1 {
+        range.1 = other_range.0.checked_sub(1)?;
+    }
+    (range.0 <= range.1).then_some(range)
+}
+
+/// Counts the IDs considered fresh by at least one range.
+///
+/// # Errors
+///
+/// Returns [`SolveError::Overflow`] when the count does not fit in a `u64`.
+pub fn solve_part_2(data: &Data) -> Result<u64, SolveError> {
+    let mut result: u64 = 0;
     let mut new_ranges: Vec<(u64, u64)> = vec![];
-    for (index, range) in data.ranges.iter().enumerate() {
+    'outer: for (index, range) in data.ranges.iter().enumerate() {
         let mut new_range = *range;
-        for other_range in &data.ranges[(index + 1)..] {
-            if other_range.0 <= new_range.0 && new_range.0 <= other_range.1 {
-                new_range.0 = other_range.1 + 1;
-            }
-            if other_range.0 <= new_range.1 && new_range.1 <= other_range.1 {
-                new_range.1 = other_range.0 - 1;
-            }
-        }
-        for other_range in &new_ranges {
-            if other_range.0 <= new_range.0 && new_range.0 <= other_range.1 {
-                new_range.0 = other_range.1 + 1;
-            }
-            if other_range.0 <= new_range.1 && new_range.1 <= other_range.1 {
-                new_range.1 = other_range.0 - 1;
+        for other_range in data.ranges[(index + 1)..].iter().chain(&new_ranges) {
+            match trim_range(new_range, *other_range) {
+                Some(range) => new_range = range,
+                None => continue 'outer,
             }
         }
         if new_range.0 <= new_range.1 {
@@ -80,18 +129,47 @@ pub fn solve_part_2(data: &Data) -> u64 {
         }
     }
     for range in &new_ranges {
-        result += range.1 - range.0 + 1;
+        let len = (range.1 - range.0)
+            .checked_add(1)
+            .ok_or(SolveError::Overflow)?;
+        result = result.checked_add(len).ok_or(SolveError::Overflow)?;
+    }
+
+    Ok(result)
+}
+
+pub struct Day5;
+
+impl Solution for Day5 {
+    const DAY: u8 = 5;
+
+    type Input = Data;
+    type Answer = u64;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_data(input)
+    }
+
+    fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_1(input)
     }
 
-    result
+    fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use common::{GoldenError, check_inputs};
+
     use super::*;
 
     #[test]
-    fn example_solve_part_1() {
+    fn example_solve_part_1() -> Result<(), ParseError> {
         let input = "3-5
 10-14
 16-20
@@ -104,13 +182,15 @@ mod tests {
 17
 32
 ";
-        let input = parse_data(input);
+        let input = parse_data(input)?;
         let result = solve_part_1(&input);
-        assert_eq!(result, 3);
+        assert_eq!(result, Ok(3));
+
+        Ok(())
     }
 
     #[test]
-    fn example_solve_part_2() {
+    fn example_solve_part_2() -> Result<(), ParseError> {
         let input = "3-5
 10-14
 16-20
@@ -123,24 +203,63 @@ mod tests {
 17
 32
 ";
-        let input = parse_data(input);
+        let input = parse_data(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 14);
+        assert_eq!(result, Ok(14));
+
+        Ok(())
     }
 
     #[test]
-    fn custom_solve_part_2() {
+    fn custom_solve_part_2() -> Result<(), ParseError> {
         let input = "110-120
 100-200
 ";
-        let input = parse_data(input);
+        let input = parse_data(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 101);
+        assert_eq!(result, Ok(101));
         let input = "100-200
 110-120
 ";
-        let input = parse_data(input);
+        let input = parse_data(input)?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(101));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_parse_data() {
+        let input = "3-5
+10-1x4
+
+1
+five
+";
+        let result = parse_data(input);
+        assert_eq!(result.err(), Some(ParseError::new(2, 4, "last ID", "1x4")));
+        let result = parse_data_lenient(input);
+        assert_eq!(result.ranges, vec![(3, 5)]);
+        assert_eq!(result.ids, vec![1]);
+    }
+
+    #[test]
+    fn limit_solve_part_2() -> Result<(), ParseError> {
+        let input = parse_data("0-5\n3-4\n18446744073709551610-18446744073709551615\n")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(12));
+        let input = parse_data("0-18446744073709551615\n")?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 101);
+        assert_eq!(result, Err(SolveError::Overflow));
+
+        Ok(())
+    }
+
+    #[test]
+    fn golden_inputs() -> Result<(), GoldenError> {
+        let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        check_inputs::<Day5>(&inputs)?;
+
+        Ok(())
     }
 }
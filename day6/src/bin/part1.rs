@@ -1,16 +1,15 @@
-use std::io::{self, Read};
+use std::error::Error;
+use std::process::ExitCode;
 
-use day6::{Problem, parse_homework_1, solve_part_1};
+use common::solve_inputs;
+use day6::{parse_homework_1, solve_part_1};
 
-fn get_input() -> Vec<Problem> {
-    let mut buf = String::new();
-    let _ = io::stdin().read_to_string(&mut buf);
+fn solve(input: &str) -> Result<u64, Box<dyn Error>> {
+    let input = parse_homework_1(input)?;
 
-    parse_homework_1(&buf)
+    Ok(solve_part_1(&input)?)
 }
 
-fn main() {
-    let input = get_input();
-    let result = solve_part_1(&input);
-    println!("{result}");
+fn main() -> ExitCode {
+    solve_inputs(solve)
 }
@@ -0,0 +1,84 @@
+use std::fmt::Write;
+
+use common::Rng;
+
+/// Size knobs of [`generate`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub problems: usize,
+    /// Numbers per problem, one per line.
+    pub rows: usize,
+    /// Most digits of a number, the width of its column.
+    pub max_digits: u32,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            problems: 1000,
+            rows: 4,
+            max_digits: 4,
+        }
+    }
+}
+
+/// Generates a worksheet readable both ways: the numbers of a problem share a
+/// column as wide as the longest of them and are randomly aligned to its left
+/// or right, with the operators on the last line.
+#[must_use]
+pub fn generate(seed: u64, options: &Options) -> String {
+    let mut rng = Rng::new(seed);
+    let max_digits = options.max_digits.clamp(1, 19);
+    let mut lines = vec![String::new(); options.rows];
+    let mut ops = String::new();
+    for problem in 0..options.problems {
+        let numbers: Vec<String> = (0..options.rows)
+            .map(|_| {
+                let digits = rng.range(1..=u64::from(max_digits));
+                (0..digits).map(|_| rng.range(1..=9).to_string()).collect()
+            })
+            .collect();
+        let width = numbers.iter().map(String::len).max().unwrap_or(1);
+        let left = rng.chance(50);
+
+        let separator = if problem == 0 { "" } else { " " };
+        for (line, number) in lines.iter_mut().zip(&numbers) {
+            line.push_str(separator);
+            if left {
+                let _ = write!(line, "{number:<width$}");
+            } else {
+                let _ = write!(line, "{number:>width$}");
+            }
+        }
+        ops.push_str(separator);
+        let op = if rng.chance(50) { '+' } else { '*' };
+        let _ = write!(ops, "{op:<width$}");
+    }
+
+    let mut input = String::new();
+    for line in lines.iter().chain([&ops]) {
+        input.push_str(line);
+        input.push('\n');
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{GeneratorError, check_generator};
+
+    use super::*;
+    use crate::Day6;
+
+    #[test]
+    fn generated_input_parses() -> Result<(), GeneratorError> {
+        let options = Options::default();
+        for input in check_generator::<Day6>(|seed| generate(seed, &options))? {
+            assert_eq!(input.rows.as_ref().map(Vec::len), Ok(options.problems));
+            assert_eq!(input.columns.as_ref().map(Vec::len), Ok(options.problems));
+        }
+
+        Ok(())
+    }
+}
@@ -1,15 +1,114 @@
+pub mod generate;
+
+use std::error::Error;
+use std::fmt;
+
+use common::{ParseError, Solution, Token, lines};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveError {
+    /// The worksheet does not fit the layout the part reads it with.
+    Layout(ParseError),
+    /// The answer to the problem at this index, or the grand total, does not fit in a `u64`.
+    Overflow { problem: usize },
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Layout(err) => write!(f, "{err}"),
+            Self::Overflow { problem } => {
+                write!(f, "problem {problem} overflows a u64")
+            }
+        }
+    }
+}
+
+impl Error for SolveError {}
+
 pub enum Problem {
     Add(Vec<u64>),
     Multiply(Vec<u64>),
 }
 
+/// The worksheet read both ways, each part solving its own layout: number by
+/// number along the rows, or digit by digit down the columns.
+pub struct Homework {
+    pub rows: Result<Vec<Problem>, ParseError>,
+    pub columns: Result<Vec<Problem>, ParseError>,
+}
+
 enum ParsedOps {
     Add,
     Multiply,
 }
 
+const fn parse_ops(c: char) -> Option<ParsedOps> {
+    match c {
+        '+' => Some(ParsedOps::Add),
+        '*' => Some(ParsedOps::Multiply),
+        _ => None,
+    }
+}
+
+fn create_problem(ops: &ParsedOps, problem: Vec<u64>) -> Problem {
+    match ops {
+        ParsedOps::Add => Problem::Add(problem),
+        ParsedOps::Multiply => Problem::Multiply(problem),
+    }
+}
+
+/// Parses the worksheet row by row: each column holds one number per line and
+/// the operator on the last line.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on a word that is neither a number nor an operator,
+/// or on a line that does not hold one number per operator.
+pub fn parse_homework_1(input: &str) -> Result<Vec<Problem>, ParseError> {
+    let mut parsed_lines = vec![];
+    let mut parsed_ops = vec![];
+
+    for line in lines(input) {
+        let mut parsed_line = vec![];
+
+        for word in line.words() {
+            match word.text {
+                "+" => parsed_ops.push(ParsedOps::Add),
+                "*" => parsed_ops.push(ParsedOps::Multiply),
+                _ => parsed_line.push((word.parse::<u64>("number, `+` or `*`")?, word)),
+            }
+        }
+        if !parsed_line.is_empty() {
+            parsed_lines.push((line, parsed_line));
+        }
+    }
+
+    for (line, parsed_line) in &parsed_lines {
+        if let Some((_, word)) = parsed_line.get(parsed_ops.len()) {
+            return Err(word.error("one number per operator"));
+        }
+        if parsed_line.len() < parsed_ops.len() {
+            return Err(line.end().error("one number per operator"));
+        }
+    }
+
+    let mut problems = vec![];
+    for (index, ops) in parsed_ops.iter().enumerate() {
+        let problem = parsed_lines
+            .iter()
+            .map(|(_, parsed_line)| parsed_line[index].0)
+            .collect();
+        problems.push(create_problem(ops, problem));
+    }
+
+    Ok(problems)
+}
+
+/// Same as [`parse_homework_1`] but silently skips the words that are neither
+/// numbers nor operators.
 #[must_use]
-pub fn parse_homework_1(input: &str) -> Vec<Problem> {
+pub fn parse_homework_1_lenient(input: &str) -> Vec<Problem> {
     let mut problems = vec![];
     let mut parsed_lines = vec![];
     let mut parsed_ops = vec![];
@@ -34,7 +133,9 @@ pub fn parse_homework_1(input: &str) -> Vec<Problem> {
         let mut problem = vec![];
 
         for parsed_line in &parsed_lines {
-            problem.push(parsed_line[index]);
+            if let Some(number) = parsed_line.get(index) {
+                problem.push(*number);
+            }
         }
         match ops {
             ParsedOps::Add => problems.push(Problem::Add(problem)),
@@ -45,8 +146,106 @@ pub fn parse_homework_1(input: &str) -> Vec<Problem> {
     problems
 }
 
+fn parse_ops_line(line: Token) -> Result<Vec<(usize, ParsedOps)>, ParseError> {
+    let mut parsed_ops = vec![];
+
+    for (index, (c, token)) in line.chars().enumerate() {
+        if let Some(ops) = parse_ops(c) {
+            parsed_ops.push((index, ops));
+        } else if index == 0 {
+            return Err(token.error("`+` or `*`"));
+        } else if c != ' ' {
+            return Err(token.error("`+`, `*` or space"));
+        }
+    }
+
+    Ok(parsed_ops)
+}
+
+/// Parses the worksheet column by column: each character column holds the
+/// digits of one number, read from top to bottom.
+///
+/// Lines shorter than the worksheet are padded with spaces.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on a character that is neither a digit nor a space,
+/// on an operator line not starting with an operator, on a text following the
+/// operator line, on a column without any digit or on the digit making its
+/// number overflow a `u64`.
+pub fn parse_homework_2(input: &str) -> Result<Vec<Problem>, ParseError> {
+    let mut lines_digits: Vec<Vec<Option<(u64, Token)>>> = vec![];
+    let mut parsed_ops = None;
+    let mut width = 0;
+
+    for line in lines(input) {
+        width = width.max(line.text.chars().count());
+        if parsed_ops.is_some() {
+            let line = line.trim();
+            if !line.text.is_empty() {
+                return Err(line.error("end of worksheet"));
+            }
+        } else if line.text.trim_start().starts_with(['+', '*']) {
+            parsed_ops = Some((line, parse_ops_line(line)?));
+        } else {
+            let mut digits = vec![];
+            for (c, token) in line.chars() {
+                match c {
+                    ' ' => digits.push(None),
+                    c => match c.to_digit(10) {
+                        Some(digit) => digits.push(Some((u64::from(digit), token))),
+                        None => return Err(token.error("digit or space")),
+                    },
+                }
+            }
+            lines_digits.push(digits);
+        }
+    }
+
+    let Some((ops_line, parsed_ops)) = parsed_ops else {
+        return Ok(vec![]);
+    };
+    let mut problems = vec![];
+    for (index, (start, ops)) in parsed_ops.iter().enumerate() {
+        let end = parsed_ops
+            .get(index + 1)
+            .map_or(width, |(next_start, _)| next_start - 1);
+        let mut problem = vec![];
+
+        for column in *start..end {
+            let mut number: Option<u64> = None;
+            for digits in &lines_digits {
+                if let Some(Some((digit, token))) = digits.get(column) {
+                    number = number
+                        .unwrap_or(0)
+                        .checked_mul(10)
+                        .and_then(|number| number.checked_add(*digit));
+                    if number.is_none() {
+                        return Err(token.error("digit of a number fitting in a u64"));
+                    }
+                }
+            }
+            let Some(number) = number else {
+                let found = ops_line
+                    .chars()
+                    .nth(column)
+                    .map_or(Token::new("", ops_line.line, column + 1), |(_, token)| {
+                        token
+                    });
+                return Err(found.error("digit in this column"));
+            };
+            problem.push(number);
+        }
+        problems.push(create_problem(ops, problem));
+    }
+
+    Ok(problems)
+}
+
+/// Same as [`parse_homework_2`] but silently skips the characters that are
+/// neither digits nor operators.
 #[must_use]
-pub fn parse_homework_2(input: &str) -> Vec<Problem> {
+pub fn parse_homework_2_lenient(input: &str) -> Vec<Problem> {
     let mut problems = vec![];
     let mut lines = vec![];
     let mut parsed_ops = vec![];
@@ -84,16 +283,18 @@ pub fn parse_homework_2(input: &str) -> Vec<Problem> {
     for (index, ops) in parsed_ops.iter().enumerate() {
         let mut problem = vec![];
 
-        for index in 0..parsed_spaces[index] {
-            let mut number = 0;
+        let spaces = parsed_spaces.get(index).copied().unwrap_or_default();
+        for index in 0..spaces {
+            let mut number: u64 = 0;
             for line in &lines {
-                if let Some(digit) = (line.as_bytes()[base_index + index] as char).to_digit(10) {
-                    number = number * 10 + u64::from(digit);
+                let c = line.as_bytes().get(base_index + index).copied();
+                if let Some(digit) = c.and_then(|c| (c as char).to_digit(10)) {
+                    number = number.saturating_mul(10).saturating_add(u64::from(digit));
                 }
             }
             problem.push(number);
         }
-        base_index += parsed_spaces[index] + 1;
+        base_index += spaces + 1;
         match ops {
             ParsedOps::Add => problems.push(Problem::Add(problem)),
             ParsedOps::Multiply => problems.push(Problem::Multiply(problem)),
@@ -103,87 +304,262 @@ pub fn parse_homework_2(input: &str) -> Vec<Problem> {
     problems
 }
 
-fn solve_problem(problem: &Problem) -> u64 {
+fn solve_problem(problem: &Problem) -> Option<u64> {
     match problem {
         Problem::Add(items) => {
-            let mut result = 0;
+            let mut result: u64 = 0;
             for item in items {
-                result += item;
+                result = result.checked_add(*item)?;
             }
-            result
+            Some(result)
         }
         Problem::Multiply(items) => {
-            let mut result = 1;
+            let mut result: u64 = 1;
             for item in items {
-                result *= item;
+                result = result.checked_mul(*item)?;
             }
-            result
+            Some(result)
         }
     }
 }
 
-#[must_use]
-pub fn solve_part_1(problems: &[Problem]) -> u64 {
-    let mut result = 0;
-    for problem in problems {
-        result += solve_problem(problem);
+fn solve_problems(problems: &[Problem]) -> Result<u64, SolveError> {
+    let mut result: u64 = 0;
+    for (index, problem) in problems.iter().enumerate() {
+        let answer = solve_problem(problem).ok_or(SolveError::Overflow { problem: index })?;
+        result = result
+            .checked_add(answer)
+            .ok_or(SolveError::Overflow { problem: index })?;
     }
 
-    result
+    Ok(result)
 }
 
-#[must_use]
-pub fn solve_part_2(problems: &[Problem]) -> u64 {
-    let mut result = 0;
-    for problem in problems {
-        result += solve_problem(problem);
+/// Sums the answers of the problems read along the rows.
+///
+/// # Errors
+///
+/// Returns [`SolveError::Overflow`] when a problem or the grand total does not fit in a `u64`.
+pub fn solve_part_1(problems: &[Problem]) -> Result<u64, SolveError> {
+    solve_problems(problems)
+}
+
+/// Sums the answers of the problems read down the columns.
+///
+/// # Errors
+///
+/// Returns [`SolveError::Overflow`] when a problem or the grand total does not fit in a `u64`.
+pub fn solve_part_2(problems: &[Problem]) -> Result<u64, SolveError> {
+    solve_problems(problems)
+}
+
+pub struct Day6;
+
+impl Solution for Day6 {
+    const DAY: u8 = 6;
+
+    type Input = Homework;
+    type Answer = u64;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        let homework = Homework {
+            rows: parse_homework_1(input),
+            columns: parse_homework_2(input),
+        };
+        if let (Err(err), Err(_)) = (&homework.rows, &homework.columns) {
+            return Err(err.clone());
+        }
+
+        Ok(homework)
+    }
+
+    fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        match &input.rows {
+            Ok(problems) => solve_part_1(problems),
+            Err(err) => Err(SolveError::Layout(err.clone())),
+        }
     }
 
-    result
+    fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        match &input.columns {
+            Ok(problems) => solve_part_2(problems),
+            Err(err) => Err(SolveError::Layout(err.clone())),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use common::{GoldenError, check_inputs};
+
     use super::*;
 
     #[test]
-    fn example_solve_part_1() {
+    fn example_solve_part_1() -> Result<(), ParseError> {
         let input = "123 328  51 64 
  45 64  387 23 
   6 98  215 314
 *   +   *   +  ";
-        let input = parse_homework_1(input);
+        let input = parse_homework_1(input)?;
         let result = solve_part_1(&input);
-        assert_eq!(result, 4277556);
+        assert_eq!(result, Ok(4_277_556));
+
+        Ok(())
     }
 
     #[test]
-    fn example_solve_part_2() {
+    fn example_solve_part_2() -> Result<(), ParseError> {
         let input = "123 328  51 64 
  45 64  387 23 
   6 98  215 314
 *   +   *   +  ";
-        let input = parse_homework_2(input);
+        let input = parse_homework_2(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 3263827);
+        assert_eq!(result, Ok(3_263_827));
+
+        Ok(())
     }
 
     #[test]
-    fn custom_solve_part_2() {
+    fn custom_solve_part_2() -> Result<(), ParseError> {
         let input = "13   74
  9  174
  2 5941
  8 5148
 +  +   ";
-        let input = parse_homework_2(input);
+        let input = parse_homework_2(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 1 + 3928 + 55 + 191 + 7744 + 4418);
+        assert_eq!(result, Ok(1 + 3928 + 55 + 191 + 7744 + 4418));
 
         let input = "111 111
 222 222
 +   +  ";
-        let input = parse_homework_2(input);
+        let input = parse_homework_2(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 12 + 12 + 12 + 12 + 12 + 12);
+        assert_eq!(result, Ok(12 + 12 + 12 + 12 + 12 + 12));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_parse_homework_1() {
+        let input = "123 328  51 64
+ 45 64  387
+  6 98  215 314
+*   +   *   +  ";
+        let result = parse_homework_1(input).err();
+        assert_eq!(
+            result,
+            Some(ParseError::new(2, 12, "one number per operator", ""))
+        );
+        let input = "123 328  51 64
+ 45 64  3/7 23
+*   +   *   +  ";
+        let result = parse_homework_1(input).err();
+        assert_eq!(
+            result,
+            Some(ParseError::new(2, 9, "number, `+` or `*`", "3/7"))
+        );
+    }
+
+    #[test]
+    fn invalid_parse_homework_2() {
+        let input = "123 328  51 64
+ 45 64  3/7 23
+*   +   *   +  ";
+        let result = parse_homework_2(input).err();
+        assert_eq!(result, Some(ParseError::new(2, 10, "digit or space", "/")));
+        let input = "123 328
+ 45 64
+ *  +  ";
+        let result = parse_homework_2(input).err();
+        assert_eq!(result, Some(ParseError::new(3, 1, "`+` or `*`", " ")));
+        let input = "12  3
+45  6
+*   +";
+        let result = parse_homework_2(input).err();
+        assert_eq!(
+            result,
+            Some(ParseError::new(3, 3, "digit in this column", " "))
+        );
+        let input = format!("{}+ ", "9\n".repeat(20));
+        let result = parse_homework_2(&input).err();
+        assert_eq!(
+            result,
+            Some(ParseError::new(
+                20,
+                1,
+                "digit of a number fitting in a u64",
+                "9"
+            ))
+        );
+    }
+
+    #[test]
+    fn trimmed_parse_homework_2() -> Result<(), ParseError> {
+        let input = "123 328  51 64
+ 45 64  387 23
+  6 98  215 314
+*   +   *   +";
+        let input = parse_homework_2(input)?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Ok(3_263_827));
+
+        Ok(())
+    }
+
+    #[test]
+    fn overflow_solve_part_1() -> Result<(), ParseError> {
+        let input = "1 4294967296
+1 4294967296
++ *";
+        let input = parse_homework_1(input)?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Err(SolveError::Overflow { problem: 1 }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn ragged_parse_lenient() {
+        let input = "123 328
+ 45
+*   +   *";
+        let result = parse_homework_1_lenient(input);
+        assert_eq!(solve_part_1(&result), Ok(123 * 45 + 328 + 1));
+        let result = parse_homework_2_lenient(input);
+        assert_eq!(result.len(), 3);
+    }
+
+    #[test]
+    fn layout_per_part() -> Result<(), ParseError> {
+        let input = Day6::parse(
+            "123 328
+ 45 64
+ *  +  ",
+        )?;
+        assert_eq!(Day6::solve_part_1(&input), Ok(123 * 45 + 328 + 64));
+        assert_eq!(
+            Day6::solve_part_2(&input),
+            Err(SolveError::Layout(ParseError::new(3, 1, "`+` or `*`", " ")))
+        );
+        let result = Day6::parse("12 x\n+ +").err();
+        assert_eq!(
+            result,
+            Some(ParseError::new(1, 4, "number, `+` or `*`", "x"))
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn golden_inputs() -> Result<(), GoldenError> {
+        let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        check_inputs::<Day6>(&inputs)?;
+
+        Ok(())
     }
 }
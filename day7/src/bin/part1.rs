@@ -1,16 +1,15 @@
-use std::io::{self, Read};
+use std::error::Error;
+use std::process::ExitCode;
 
-use day7::{Item, parse_grid, solve_part_1};
+use common::solve_inputs;
+use day7::{parse_grid, solve_part_1};
 
-fn get_input() -> Vec<Vec<Item>> {
-    let mut buf = String::new();
-    let _ = io::stdin().read_to_string(&mut buf);
+fn solve(input: &str) -> Result<u64, Box<dyn Error>> {
+    let mut input = parse_grid(input)?;
 
-    parse_grid(&buf)
+    Ok(solve_part_1(&mut input)?)
 }
 
-fn main() {
-    let mut input = get_input();
-    let result = solve_part_1(&mut input);
-    println!("{result}");
+fn main() -> ExitCode {
+    solve_inputs(solve)
 }
@@ -0,0 +1,71 @@
+use common::Rng;
+
+/// Size knobs of [`generate`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub width: usize,
+    pub height: usize,
+    /// Percentage of the candidate cells holding a splitter.
+    pub density: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            width: 141,
+            height: 142,
+            density: 50,
+        }
+    }
+}
+
+/// Generates a manifold with the source `S` in the middle of the first row and
+/// splitters `^` on every other row, within the cone the beams can reach, never
+/// next to each other nor on the edges.
+#[must_use]
+pub fn generate(seed: u64, options: &Options) -> String {
+    let mut rng = Rng::new(seed);
+    let center = options.width / 2;
+    let mut input = String::new();
+    for y in 0..options.height {
+        for x in 0..options.width {
+            let c = if y == 0 && x == center {
+                'S'
+            } else if y > 0
+                && y % 2 == 0
+                && x > 0
+                && x + 1 < options.width
+                && x.abs_diff(center) < y / 2
+                && (x + y / 2) % 2 == (center + 1) % 2
+                && rng.chance(options.density)
+            {
+                '^'
+            } else {
+                '.'
+            };
+            input.push(c);
+        }
+        input.push('\n');
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{GeneratorError, check_generator};
+
+    use super::*;
+    use crate::Day7;
+
+    #[test]
+    fn generated_input_parses() -> Result<(), GeneratorError> {
+        let options = Options::default();
+        for input in check_generator::<Day7>(|seed| generate(seed, &options))? {
+            assert_eq!(input.height(), options.height);
+        }
+        assert!(!generate(1, &options).contains("^^"));
+
+        Ok(())
+    }
+}
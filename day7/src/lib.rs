@@ -1,4 +1,27 @@
-#[derive(Clone, Copy)]
+pub mod generate;
+
+use std::error::Error;
+use std::fmt;
+
+use common::{Grid, ParseError, Point, Solution, Token};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveError {
+    /// The count of timelines does not fit in a `u64`.
+    Overflow,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Overflow => write!(f, "the count of timelines overflows a u64"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum Item {
     Source,
     Empty,
@@ -17,110 +40,158 @@ const fn parse_item(c: char) -> Item {
     }
 }
 
-pub fn parse_grid(str: &str) -> Vec<Vec<Item>> {
-    let mut grid = vec![];
+impl fmt::Display for Item {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Source => write!(f, "S"),
+            Self::Empty => write!(f, "."),
+            Self::Splitter => write!(f, "^"),
+            Self::Tachyon(_) => write!(f, "|"),
+            Self::Other => write!(f, "?"),
+        }
+    }
+}
 
-    for line in str.split_whitespace() {
-        let row = line.chars().map(parse_item).collect();
-        grid.push(row);
+fn parse_known_item(c: char, token: Token) -> Result<Item, ParseError> {
+    match parse_item(c) {
+        Item::Other => Err(token.error("`S`, `.`, `^` or `|`")),
+        item => Ok(item),
     }
-    grid
 }
 
-#[allow(unused_mut)]
+/// Parses the tachyon manifold: the source `S`, empty space `.`, splitters `^`
+/// and beams `|`.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on the first character that is not part of a manifold
+/// and on the first row not as wide as the first one.
+pub fn parse_grid(str: &str) -> Result<Grid<Item>, ParseError> {
+    Grid::parse(str, parse_known_item)
+}
+
+/// Same as [`parse_grid`] but keeps the unknown characters as [`Item::Other`],
+/// padding the short rows with it too.
 #[must_use]
-pub fn solve_part_1(grid: &mut [Vec<Item>]) -> u64 {
+pub fn parse_grid_lenient(str: &str) -> Grid<Item> {
+    Grid::parse_lenient(str, parse_item, Item::Other)
+}
+
+/// Timelines of the beam reaching `point` from the row above: straight down
+/// when `dx` is 0, otherwise off a splitter `dx` columns away.
+fn beam_from(grid: &Grid<Item>, point: Point, dx: isize) -> Option<u64> {
+    if dx != 0 && grid.offset(point, dx, 0).map(|side| grid[side]) != Some(Item::Splitter) {
+        return None;
+    }
+    match grid[grid.offset(point, dx, -1)?] {
+        Item::Tachyon(i) => Some(i),
+        _ => None,
+    }
+}
+
+/// Counts the splitters hit by a beam, marking the beams in `grid` on the way.
+///
+/// # Errors
+///
+/// Never fails, the error is there to match [`solve_part_2`].
+pub fn solve_part_1(grid: &mut Grid<Item>) -> Result<u64, SolveError> {
     let mut result = 0;
-    for y in 0..grid.len() {
-        for x in 0..grid[y].len() {
-            grid[y][x] = match &grid[y][x] {
-                Item::Source => Item::Tachyon(1),
-                Item::Empty => {
-                    if y > 0
-                        && (matches!(grid[y - 1][x], Item::Tachyon(1))
-                            || (x > 0
-                                && matches!(grid[y][x - 1], Item::Splitter)
-                                && matches!(grid[y - 1][x - 1], Item::Tachyon(1)))
-                            || (x < grid[y].len() - 1
-                                && matches!(grid[y][x + 1], Item::Splitter)
-                                && matches!(grid[y - 1][x + 1], Item::Tachyon(1))))
-                    {
-                        Item::Tachyon(1)
-                    } else {
-                        Item::Empty
-                    }
+    for point in grid.points() {
+        grid[point] = match grid[point] {
+            Item::Source => Item::Tachyon(1),
+            Item::Empty => {
+                if [0, -1, 1]
+                    .into_iter()
+                    .any(|dx| beam_from(grid, point, dx) == Some(1))
+                {
+                    Item::Tachyon(1)
+                } else {
+                    Item::Empty
                 }
-                Item::Splitter => {
-                    if y > 0 && matches!(grid[y - 1][x], Item::Tachyon(1)) {
-                        result += 1;
-                    }
-                    Item::Splitter
+            }
+            Item::Splitter => {
+                if beam_from(grid, point, 0) == Some(1) {
+                    result += 1;
                 }
-                item => *item,
-            };
-        }
+                Item::Splitter
+            }
+            item => item,
+        };
     }
 
-    result
+    Ok(result)
 }
 
-#[must_use]
-pub fn solve_part_2(grid: &mut [Vec<Item>]) -> u64 {
-    let mut result = 0;
-    for y in 0..grid.len() {
-        for x in 0..grid[y].len() {
-            grid[y][x] = match &grid[y][x] {
-                Item::Source => Item::Tachyon(1),
-                Item::Empty => {
-                    let mut tachyon = 0;
-                    if y > 0
-                        && let Item::Tachyon(i) = grid[y - 1][x]
-                    {
-                        tachyon += i;
-                    }
-                    if y > 0
-                        && x > 0
-                        && matches!(grid[y][x - 1], Item::Splitter)
-                        && let Item::Tachyon(i) = grid[y - 1][x - 1]
-                    {
-                        tachyon += i;
-                    }
-                    if y > 0
-                        && x < grid[y].len() - 1
-                        && matches!(grid[y][x + 1], Item::Splitter)
-                        && let Item::Tachyon(i) = grid[y - 1][x + 1]
-                    {
-                        tachyon += i;
-                    }
-                    if tachyon != 0 {
-                        Item::Tachyon(tachyon)
-                    } else {
-                        Item::Empty
+/// Counts the timelines of a single tachyon, marking the beams in `grid` on the way.
+///
+/// # Errors
+///
+/// Returns [`SolveError::Overflow`] when the count of timelines does not fit in a `u64`.
+pub fn solve_part_2(grid: &mut Grid<Item>) -> Result<u64, SolveError> {
+    let mut result: u64 = 0;
+    for point in grid.points() {
+        grid[point] = match grid[point] {
+            Item::Source => Item::Tachyon(1),
+            Item::Empty => {
+                let mut tachyon: u64 = 0;
+                for dx in [0, -1, 1] {
+                    if let Some(i) = beam_from(grid, point, dx) {
+                        tachyon = tachyon.checked_add(i).ok_or(SolveError::Overflow)?;
                     }
                 }
-                Item::Splitter => Item::Splitter,
-                item => *item,
-            };
-        }
+                if tachyon != 0 {
+                    Item::Tachyon(tachyon)
+                } else {
+                    Item::Empty
+                }
+            }
+            item => item,
+        };
     }
 
-    if let Some(line) = grid.last() {
+    if let Some(line) = grid.rows().last() {
         for item in line {
             if let Item::Tachyon(i) = *item {
-                result += i;
+                result = result.checked_add(i).ok_or(SolveError::Overflow)?;
             }
         }
     }
 
-    result
+    Ok(result)
+}
+
+pub struct Day7;
+
+impl Solution for Day7 {
+    const DAY: u8 = 7;
+
+    type Input = Grid<Item>;
+    type Answer = u64;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_grid(input)
+    }
+
+    fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_1(&mut input.clone())
+    }
+
+    fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_2(&mut input.clone())
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use common::{GoldenError, check_inputs};
+
     use super::*;
 
     #[test]
-    fn example_solve_part_1() {
+    fn example_solve_part_1() -> Result<(), ParseError> {
         let input = ".......S.......
 ...............
 .......^.......
@@ -137,13 +208,15 @@ mod tests {
 ...............
 .^.^.^.^.^...^.
 ...............";
-        let mut input = parse_grid(input);
+        let mut input = parse_grid(input)?;
         let result = solve_part_1(&mut input);
-        assert_eq!(result, 21);
+        assert_eq!(result, Ok(21));
+
+        Ok(())
     }
 
     #[test]
-    fn example_solve_part_2() {
+    fn example_solve_part_2() -> Result<(), ParseError> {
         let input = ".......S.......
 ...............
 .......^.......
@@ -160,29 +233,31 @@ mod tests {
 ...............
 .^.^.^.^.^...^.
 ...............";
-        let mut input = parse_grid(input);
+        let mut input = parse_grid(input)?;
         let result = solve_part_2(&mut input);
-        assert_eq!(result, 40);
+        assert_eq!(result, Ok(40));
+
+        Ok(())
     }
 
     #[test]
-    fn custom_solve_part_2() {
+    fn custom_solve_part_2() -> Result<(), ParseError> {
         let input = ".......S.......
 ...............
 .......^.......
 ...............";
-        let mut input = parse_grid(input);
+        let mut input = parse_grid(input)?;
         let result = solve_part_2(&mut input);
-        assert_eq!(result, 2);
+        assert_eq!(result, Ok(2));
         let input = ".......S.......
 ...............
 .......^.......
 ...............
 ......^.^......
 ...............";
-        let mut input = parse_grid(input);
+        let mut input = parse_grid(input)?;
         let result = solve_part_2(&mut input);
-        assert_eq!(result, 4);
+        assert_eq!(result, Ok(4));
         let input = ".......S.......
 ...............
 .......^.......
@@ -191,8 +266,48 @@ mod tests {
 ...............
 .....^.^.^.....
 ...............";
-        let mut input = parse_grid(input);
+        let mut input = parse_grid(input)?;
         let result = solve_part_2(&mut input);
-        assert_eq!(result, 8);
+        assert_eq!(result, Ok(8));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_parse_grid() {
+        let input = "...S...
+...v...";
+        let result = parse_grid(input);
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 4, "`S`, `.`, `^` or `|`", "v"))
+        );
+        let result = parse_grid_lenient(input);
+        assert_eq!(result[Point::new(3, 1)], Item::Other);
+    }
+
+    #[test]
+    fn ragged_parse_grid() -> Result<(), ParseError> {
+        let input = "..S..
+...
+";
+        let result = parse_grid(input);
+        assert_eq!(result, Err(ParseError::new(2, 1, "row of 5 cells", "...")));
+        let result = parse_grid_lenient(input);
+        assert_eq!(result.to_string(), "..S..\n...??");
+        let mut input = parse_grid("..S..\n..^..\n.....")?;
+        assert_eq!(solve_part_1(&mut input), Ok(1));
+        assert_eq!(input.to_string(), "..|..\n.|^|.\n.|.|.");
+        assert_eq!(parse_grid(&input.to_string()), Ok(input));
+
+        Ok(())
+    }
+
+    #[test]
+    fn golden_inputs() -> Result<(), GoldenError> {
+        let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        check_inputs::<Day7>(&inputs)?;
+
+        Ok(())
     }
 }
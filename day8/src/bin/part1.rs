@@ -1,16 +1,15 @@
-use std::io::{self, Read};
+use std::error::Error;
+use std::process::ExitCode;
 
-use day8::{Coord, parse_coords, solve_part_1};
+use common::solve_inputs;
+use day8::{parse_coords, solve_part_1};
 
-fn get_input() -> Vec<Coord> {
-    let mut buf = String::new();
-    let _ = io::stdin().read_to_string(&mut buf);
+fn solve(input: &str) -> Result<u64, Box<dyn Error>> {
+    let input = parse_coords(input)?;
 
-    parse_coords(&buf)
+    Ok(solve_part_1(&input, 1000)?)
 }
 
-fn main() {
-    let input = get_input();
-    let result = solve_part_1(&input, 1000);
-    println!("{result}");
+fn main() -> ExitCode {
+    solve_inputs(solve)
 }
@@ -0,0 +1,53 @@
+use std::fmt::Write;
+
+use common::Rng;
+
+/// Size knobs of [`generate`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    pub boxes: usize,
+    /// Highest coordinate on every axis.
+    pub max_coordinate: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            boxes: 1000,
+            max_coordinate: 100_000,
+        }
+    }
+}
+
+/// Generates one junction box position `x,y,z` per line.
+#[must_use]
+pub fn generate(seed: u64, options: &Options) -> String {
+    let mut rng = Rng::new(seed);
+    let mut input = String::new();
+    for _ in 0..options.boxes {
+        let x = rng.range(0..=options.max_coordinate);
+        let y = rng.range(0..=options.max_coordinate);
+        let z = rng.range(0..=options.max_coordinate);
+        let _ = writeln!(input, "{x},{y},{z}");
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{GeneratorError, check_generator};
+
+    use super::*;
+    use crate::Day8;
+
+    #[test]
+    fn generated_input_parses() -> Result<(), GeneratorError> {
+        let options = Options::default();
+        for input in check_generator::<Day8>(|seed| generate(seed, &options))? {
+            assert_eq!(input.len(), options.boxes);
+        }
+
+        Ok(())
+    }
+}
@@ -1,4 +1,31 @@
+pub mod generate;
+
 use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+
+use common::{ParseError, Solution, Token, words};
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveError {
+    /// Fewer than three circuits are left once the junction boxes are connected.
+    NotEnoughCircuits { found: usize },
+    /// The product does not fit in a `u64`.
+    Overflow,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughCircuits { found } => {
+                write!(f, "expected at least 3 circuits, found {found}")
+            }
+            Self::Overflow => write!(f, "the product overflows a u64"),
+        }
+    }
+}
+
+impl Error for SolveError {}
 
 pub struct Coord {
     x: u64,
@@ -6,22 +33,36 @@ pub struct Coord {
     z: u64,
 }
 
-#[must_use]
-pub fn parse_coords(input: &str) -> Vec<Coord> {
-    let mut coords = vec![];
-
-    for line in input.split_whitespace() {
-        if let Some((x, yz)) = line.split_once(',')
-            && let Ok(x) = x.parse::<u64>()
-            && let Some((y, z)) = yz.split_once(',')
-            && let Ok(y) = y.parse::<u64>()
-            && let Ok(z) = z.parse::<u64>()
-        {
-            coords.push(Coord { x, y, z });
-        }
-    }
+fn parse_coord(line: Token) -> Result<Coord, ParseError> {
+    let Some((x, yz)) = line.split_once(',') else {
+        return Err(line.error("junction box position `x,y,z`"));
+    };
+    let Some((y, z)) = yz.split_once(',') else {
+        return Err(yz.error("`y,z`"));
+    };
 
-    coords
+    Ok(Coord {
+        x: x.parse("x coordinate")?,
+        y: y.parse("y coordinate")?,
+        z: z.parse("z coordinate")?,
+    })
+}
+
+/// Parses one junction box position `x,y,z` per line.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on the first line that is not a position.
+pub fn parse_coords(input: &str) -> Result<Vec<Coord>, ParseError> {
+    words(input).map(parse_coord).collect()
+}
+
+/// Same as [`parse_coords`] but silently skips the lines that are not positions.
+#[must_use]
+pub fn parse_coords_lenient(input: &str) -> Vec<Coord> {
+    words(input)
+        .filter_map(|line| parse_coord(line).ok())
+        .collect()
 }
 
 fn create_circuit(junctions: &[(usize, usize)], node: usize) -> Vec<usize> {
@@ -50,7 +91,11 @@ fn create_shortest_junction(
 ) -> Option<(usize, usize, f64)> {
     let junctions: HashSet<(usize, usize)> = junctions.iter().copied().collect();
     let mut shortest_junction = None;
-    for (index, coord) in coords.iter().enumerate().take(coords.len() - 1) {
+    for (index, coord) in coords
+        .iter()
+        .enumerate()
+        .take(coords.len().saturating_sub(1))
+    {
         for (other_index, other_coord) in coords.iter().enumerate().skip(index + 1) {
             if junctions.contains(&(index, other_index)) {
                 continue;
@@ -75,8 +120,14 @@ fn create_shortest_junction(
     shortest_junction
 }
 
-#[must_use]
-pub fn solve_part_1(coords: &[Coord], mut to_connected: u64) -> u64 {
+/// Multiplies the sizes of the three largest circuits once the `to_connected`
+/// closest pairs of junction boxes are connected.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotEnoughCircuits`] when there are fewer than three circuits
+/// and [`SolveError::Overflow`] when the product does not fit in a `u64`.
+pub fn solve_part_1(coords: &[Coord], mut to_connected: u64) -> Result<u64, SolveError> {
     let mut junctions = vec![];
     while to_connected != 0
         && let Some(junction) = create_shortest_junction(coords, &junctions)
@@ -99,13 +150,27 @@ pub fn solve_part_1(coords: &[Coord], mut to_connected: u64) -> u64 {
 
     circuits.sort_by_key(|circuit| std::cmp::Reverse(circuit.len()));
 
-    let top = &circuits[..3];
+    let [first, second, third, ..] = circuits.as_slice() else {
+        return Err(SolveError::NotEnoughCircuits {
+            found: circuits.len(),
+        });
+    };
 
-    (top[0].len() * top[1].len() * top[2].len()) as u64
+    first
+        .len()
+        .checked_mul(second.len())
+        .and_then(|product| product.checked_mul(third.len()))
+        .and_then(|product| u64::try_from(product).ok())
+        .ok_or(SolveError::Overflow)
 }
 
-#[must_use]
-pub fn solve_part_2(coords: &[Coord]) -> u64 {
+/// Multiplies the X coordinates of the last two junction boxes connected to
+/// join every box into a single circuit.
+///
+/// # Errors
+///
+/// Returns [`SolveError::Overflow`] when the product does not fit in a `u64`.
+pub fn solve_part_2(coords: &[Coord]) -> Result<u64, SolveError> {
     let mut junctions = vec![];
     while create_circuit(&junctions, 0).len() < coords.len()
         && let Some(junction) = create_shortest_junction(coords, &junctions)
@@ -116,17 +181,46 @@ pub fn solve_part_2(coords: &[Coord]) -> u64 {
 
     let mut result = 0;
     if let Some(last_junction) = junctions.last() {
-        result = coords[last_junction.0].x * coords[last_junction.1].x;
+        result = coords[last_junction.0]
+            .x
+            .checked_mul(coords[last_junction.1].x)
+            .ok_or(SolveError::Overflow)?;
+    }
+    Ok(result)
+}
+
+pub struct Day8;
+
+impl Solution for Day8 {
+    const DAY: u8 = 8;
+
+    type Input = Vec<Coord>;
+    type Answer = u64;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_coords(input)
+    }
+
+    fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_1(input, 1000)
+    }
+
+    fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_2(input)
     }
-    result
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use common::{GoldenError, check_inputs};
+
     use super::*;
 
     #[test]
-    fn example_solve_part_1() {
+    fn example_solve_part_1() -> Result<(), ParseError> {
         let input = "162,817,812
 57,618,57
 906,360,560
@@ -147,13 +241,15 @@ mod tests {
 862,61,35
 984,92,344
 425,690,689";
-        let input = parse_coords(input);
+        let input = parse_coords(input)?;
         let result = solve_part_1(&input, 10);
-        assert_eq!(result, 40);
+        assert_eq!(result, Ok(40));
+
+        Ok(())
     }
 
     #[test]
-    fn example_solve_part_2() {
+    fn example_solve_part_2() -> Result<(), ParseError> {
         let input = "162,817,812
 57,618,57
 906,360,560
@@ -174,8 +270,56 @@ mod tests {
 862,61,35
 984,92,344
 425,690,689";
-        let input = parse_coords(input);
+        let input = parse_coords(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 216 * 117);
+        assert_eq!(result, Ok(216 * 117));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_parse_coords() {
+        let input = "162,817,812
+57,618";
+        let result = parse_coords(input);
+        assert_eq!(result.err(), Some(ParseError::new(2, 4, "`y,z`", "618")));
+        let input = "162,817,812
+57,-618,57";
+        let result = parse_coords(input);
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 4, "y coordinate", "-618"))
+        );
+        let result = parse_coords_lenient(input);
+        assert_eq!(result.len(), 1);
+    }
+
+    #[test]
+    fn not_enough_solve_part_1() -> Result<(), ParseError> {
+        let input = parse_coords("")?;
+        let result = solve_part_1(&input, 10);
+        assert_eq!(result, Err(SolveError::NotEnoughCircuits { found: 0 }));
+        let input = parse_coords("1,2,3\n4,5,6\n7,8,9\n")?;
+        let result = solve_part_1(&input, 1);
+        assert_eq!(result, Err(SolveError::NotEnoughCircuits { found: 2 }));
+
+        Ok(())
+    }
+
+    #[test]
+    fn overflow_solve_part_2() -> Result<(), ParseError> {
+        let input = parse_coords("4294967296,0,0\n4294967297,0,0\n")?;
+        let result = solve_part_2(&input);
+        assert_eq!(result, Err(SolveError::Overflow));
+
+        Ok(())
+    }
+
+    #[test]
+    fn golden_inputs() -> Result<(), GoldenError> {
+        let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        check_inputs::<Day8>(&inputs)?;
+
+        Ok(())
     }
 }
@@ -0,0 +1,104 @@
+use std::fmt::Write;
+
+use common::Rng;
+
+/// Size knobs of [`generate`].
+#[derive(Debug, Clone, Copy)]
+pub struct Options {
+    /// Vertical strips the polygon is made of, it has four corners per strip.
+    pub columns: usize,
+    /// Widest strip.
+    pub max_width: u64,
+    /// Height of the polygon bounding box, at least 4.
+    pub height: u64,
+}
+
+impl Default for Options {
+    fn default() -> Self {
+        Self {
+            columns: 20,
+            max_width: 10,
+            height: 40,
+        }
+    }
+}
+
+/// Random value of `range` other than `previous`, when the range allows it.
+fn other_than(rng: &mut Rng, low: u64, high: u64, previous: Option<u64>) -> u64 {
+    let value = rng.range(low..=high);
+    match previous {
+        Some(previous) if previous == value && low < high => {
+            if value == high {
+                low
+            } else {
+                value + 1
+            }
+        }
+        _ => value,
+    }
+}
+
+/// Generates the red tiles, one `x,y` per line, at the corners of a simple
+/// rectilinear polygon listed in order: strips side by side whose bottoms lie in
+/// the lower half and tops in the upper half, so neighbouring strips always overlap.
+#[must_use]
+pub fn generate(seed: u64, options: &Options) -> String {
+    let mut rng = Rng::new(seed);
+    let height = options.height.max(4);
+    let middle = height / 2;
+
+    let mut xs = vec![rng.range(0..=options.max_width)];
+    let mut tops: Vec<u64> = vec![];
+    let mut bottoms: Vec<u64> = vec![];
+    for _ in 0..options.columns.max(1) {
+        let x = xs.last().copied().unwrap_or_default();
+        xs.push(x + rng.range(1..=options.max_width.max(1)));
+        tops.push(other_than(
+            &mut rng,
+            middle + 1,
+            height,
+            tops.last().copied(),
+        ));
+        bottoms.push(other_than(&mut rng, 0, middle - 1, bottoms.last().copied()));
+    }
+
+    let mut corners = vec![];
+    for (index, top) in tops.iter().enumerate() {
+        corners.push((xs[index], *top));
+        corners.push((xs[index + 1], *top));
+    }
+    for (index, bottom) in bottoms.iter().enumerate().rev() {
+        corners.push((xs[index + 1], *bottom));
+        corners.push((xs[index], *bottom));
+    }
+
+    let mut input = String::new();
+    for (x, y) in corners {
+        let _ = writeln!(input, "{x},{y}");
+    }
+
+    input
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{GeneratorError, check_generator};
+
+    use super::*;
+    use crate::{Day9, solve_part_2};
+
+    #[test]
+    fn generated_input_parses() -> Result<(), GeneratorError> {
+        let options = Options::default();
+        for input in check_generator::<Day9>(|seed| generate(seed, &options))? {
+            assert_eq!(input.len(), options.columns * 4);
+            for (index, coord) in input.iter().enumerate() {
+                let next = &input[(index + 1) % input.len()];
+                assert!((coord.x == next.x) != (coord.y == next.y));
+            }
+            assert!(solve_part_2(&input).is_ok_and(|area| area > 0));
+        }
+
+        Ok(())
+    }
+}
@@ -1,53 +1,103 @@
-use rayon::prelude::*;
-use std::collections::HashSet;
+pub mod generate;
+pub mod reference;
 
-#[derive(PartialEq, Eq, Hash, Clone, Copy)]
+use common::{ParseError, Solution, Token, words};
+use std::error::Error;
+use std::fmt;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum SolveError {
+    /// Fewer than two red tiles, no rectangle can be drawn.
+    NotEnoughTiles { found: usize },
+    /// The area of a rectangle does not fit in a `u64`.
+    Overflow,
+}
+
+impl fmt::Display for SolveError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NotEnoughTiles { found } => {
+                write!(f, "expected at least 2 red tiles, found {found}")
+            }
+            Self::Overflow => write!(f, "the area of a rectangle overflows a u64"),
+        }
+    }
+}
+
+impl Error for SolveError {}
+
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
 pub struct Coord {
     x: u64,
     y: u64,
 }
 
+fn parse_coord(line: Token) -> Result<Coord, ParseError> {
+    let Some((x, y)) = line.split_once(',') else {
+        return Err(line.error("red tile position `x,y`"));
+    };
+
+    Ok(Coord {
+        x: x.parse("x coordinate")?,
+        y: y.parse("y coordinate")?,
+    })
+}
+
+/// Parses one red tile position `x,y` per line.
+///
+/// # Errors
+///
+/// Returns a [`ParseError`] on the first line that is not a position.
+pub fn parse_coords(input: &str) -> Result<Vec<Coord>, ParseError> {
+    words(input).map(parse_coord).collect()
+}
+
+/// Same as [`parse_coords`] but silently skips the lines that are not positions.
 #[must_use]
-pub fn parse_coords(input: &str) -> Vec<Coord> {
-    let mut coords = vec![];
-
-    for line in input.split_whitespace() {
-        if let Some((x, y)) = line.split_once(',')
-            && let Ok(x) = x.parse::<u64>()
-            && let Ok(y) = y.parse::<u64>()
-        {
-            coords.push(Coord { x, y });
-        }
+pub fn parse_coords_lenient(input: &str) -> Vec<Coord> {
+    words(input)
+        .filter_map(|line| parse_coord(line).ok())
+        .collect()
+}
+
+fn check_coords(coords: &[Coord]) -> Result<(), SolveError> {
+    if coords.len() < 2 {
+        return Err(SolveError::NotEnoughTiles {
+            found: coords.len(),
+        });
     }
 
-    coords
+    Ok(())
 }
 
-#[must_use]
-pub fn solve_part_1(coords: &[Coord]) -> u64 {
+/// Area, borders included, of the rectangle with `a` and `b` as opposite corners.
+fn area(a: &Coord, b: &Coord) -> Result<u64, SolveError> {
+    let width = a.x.abs_diff(b.x).checked_add(1);
+    let height = a.y.abs_diff(b.y).checked_add(1);
+    width
+        .zip(height)
+        .and_then(|(width, height)| width.checked_mul(height))
+        .ok_or(SolveError::Overflow)
+}
+
+/// Finds the largest rectangle having two red tiles as opposite corners.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotEnoughTiles`] when there are fewer than two red tiles
+/// and [`SolveError::Overflow`] when an area does not fit in a `u64`.
+pub fn solve_part_1(coords: &[Coord]) -> Result<u64, SolveError> {
+    check_coords(coords)?;
     let mut result = 0;
     for (index, coord_a) in coords.iter().enumerate().take(coords.len() - 1) {
         for coord_b in coords.iter().skip(index + 1) {
-            let area = (coord_a.x.abs_diff(coord_b.x) + 1) * (coord_a.y.abs_diff(coord_b.y) + 1);
+            let area = area(coord_a, coord_b)?;
             if result < area {
                 result = area;
             }
         }
     }
-    result
-}
-
-fn is_inside(vertical_segments: &[(Coord, Coord)], point: &Coord) -> bool {
-    let mut intersects = 0;
-    for vertical_segment in vertical_segments {
-        if vertical_segment.0.x > point.x
-            && (vertical_segment.0.y > point.y) != (vertical_segment.1.y > point.y)
-        {
-            intersects += 1;
-        }
-    }
-
-    intersects % 2 == 1
+    Ok(result)
 }
 
 fn create_segments(coords: &[Coord]) -> Vec<(Coord, Coord)> {
@@ -64,51 +114,6 @@ fn create_segments(coords: &[Coord]) -> Vec<(Coord, Coord)> {
     segments
 }
 
-fn create_line(segment: &(Coord, Coord)) -> Vec<Coord> {
-    let mut line = vec![];
-    if segment.0.x == segment.1.x {
-        let x = segment.0.x;
-        let y_range = if segment.0.y <= segment.1.y {
-            (segment.0.y + 1)..(segment.1.y + 1)
-        } else {
-            (segment.1.y)..segment.0.y
-        };
-        for y in y_range {
-            line.push(Coord { x, y });
-        }
-    } else if segment.0.y == segment.1.y {
-        let y = segment.0.y;
-        let x_range = if segment.0.x <= segment.1.x {
-            (segment.0.x + 1)..(segment.1.x + 1)
-        } else {
-            segment.1.x..segment.0.x
-        };
-        for x in x_range {
-            line.push(Coord { x, y });
-        }
-    }
-
-    line
-}
-
-fn create_border_tiles(segments: &[(Coord, Coord)]) -> Vec<Coord> {
-    let mut border_tiles = vec![];
-    for segment in segments {
-        let mut line = create_line(segment);
-        border_tiles.append(&mut line);
-    }
-
-    border_tiles
-}
-
-fn is_valid_tile(
-    border_tiles: &HashSet<Coord>,
-    vertical_segments: &[(Coord, Coord)],
-    tile: &Coord,
-) -> bool {
-    border_tiles.contains(tile) || is_inside(vertical_segments, tile)
-}
-
 fn get_min_max(rectangle: &(Coord, Coord)) -> (Coord, Coord) {
     let (min_x, max_x) = if rectangle.0.x <= rectangle.1.x {
         (rectangle.0.x, rectangle.1.x)
@@ -124,108 +129,138 @@ fn get_min_max(rectangle: &(Coord, Coord)) -> (Coord, Coord) {
     (Coord { x: min_x, y: min_y }, Coord { x: max_x, y: max_y })
 }
 
-fn is_valide_rectangle(
-    border_tiles: &HashSet<Coord>,
-    vertical_segments: &[(Coord, Coord)],
-    rectangle: &(Coord, Coord),
-) -> bool {
-    let (min, max) = get_min_max(rectangle);
-    if !is_valid_tile(
-        border_tiles,
-        vertical_segments,
-        &Coord {
-            x: rectangle.0.x,
-            y: rectangle.1.y,
-        },
-    ) || !is_valid_tile(
-        border_tiles,
-        vertical_segments,
-        &Coord {
-            x: rectangle.1.x,
-            y: rectangle.0.y,
-        },
-    ) {
-        return false;
+/// Sorted distinct `values` with one more value inside every gap between two
+/// consecutive ones: all the tiles of a gap are alike, one of them stands for all.
+fn compress(values: impl Iterator<Item = u64>) -> Vec<u64> {
+    let mut values: Vec<u64> = values.collect();
+    values.sort_unstable();
+    values.dedup();
+    let mut compressed = Vec::with_capacity(values.len() * 2);
+    for (index, &value) in values.iter().enumerate() {
+        compressed.push(value);
+        if values.get(index + 1).is_some_and(|&next| next - value > 1) {
+            compressed.push(value + 1);
+        }
     }
 
-    (min.y..=max.y).into_par_iter().all(|y| {
-        (min.x..=max.x).all(|x| {
-            let tile = Coord { x, y };
-            is_valid_tile(border_tiles, vertical_segments, &tile)
-        })
-    })
+    compressed
 }
 
-fn create_rectangles(red_coords: &[Coord]) -> Vec<(Coord, Coord, u64)> {
-    let mut rectangles = vec![];
-    for (index, coord_a) in red_coords.iter().enumerate().take(red_coords.len() - 1) {
-        for coord_b in red_coords.iter().skip(index + 1) {
-            let rectangle = (*coord_a, *coord_b);
-            let area = (rectangle.0.x.abs_diff(rectangle.1.x) + 1)
-                * (rectangle.0.y.abs_diff(rectangle.1.y) + 1);
-            let rectangle = (*coord_a, *coord_b, area);
-            rectangles.push(rectangle);
+fn position(compressed: &[u64], value: u64) -> usize {
+    compressed
+        .binary_search(&value)
+        .unwrap_or_else(|index| index)
+}
+
+/// Whether each compressed tile, row by row, is on the border or inside the loop.
+/// A tile is inside when an odd number of vertical segments cross its row on its
+/// right, a segment crossing the rows from its lowest `y` excluded to its highest.
+fn valid_tiles(segments: &[(Coord, Coord)], xs: &[u64], ys: &[u64]) -> Vec<Vec<bool>> {
+    let mut valid = vec![vec![false; xs.len()]; ys.len()];
+    // A segment covers its end but not its start, which a single tile does not.
+    for segment in segments {
+        if segment.0 != segment.1 && (segment.0.x == segment.1.x || segment.0.y == segment.1.y) {
+            let (min, max) = get_min_max(segment);
+            for row in &mut valid[position(ys, min.y)..=position(ys, max.y)] {
+                row[position(xs, min.x)..=position(xs, max.x)].fill(true);
+            }
         }
     }
 
-    rectangles
-}
-
-fn is_contains_invalid_rectangle(
-    invalid_rectangles: &[(Coord, Coord)],
-    rectangle: &(Coord, Coord),
-) -> bool {
-    let (min, max) = get_min_max(rectangle);
-    for invalid_rectangle in invalid_rectangles {
-        if min.x <= invalid_rectangle.0.x
-            && invalid_rectangle.0.x <= max.x
-            && min.x <= invalid_rectangle.1.x
-            && invalid_rectangle.1.x <= max.x
-            && min.y <= invalid_rectangle.0.y
-            && invalid_rectangle.0.y <= max.y
-            && min.y <= invalid_rectangle.1.y
-            && invalid_rectangle.1.y <= max.y
-        {
-            return true;
+    for (row, &y) in valid.iter_mut().zip(ys) {
+        let mut crossings: Vec<u64> = segments
+            .iter()
+            .filter(|segment| segment.0.x == segment.1.x && (segment.0.y > y) != (segment.1.y > y))
+            .map(|segment| segment.0.x)
+            .collect();
+        crossings.sort_unstable();
+        let mut left = 0;
+        for (tile, &x) in row.iter_mut().zip(xs) {
+            while crossings.get(left).is_some_and(|&crossing| crossing <= x) {
+                left += 1;
+            }
+            if (crossings.len() - left) % 2 == 1 {
+                *tile = true;
+            }
         }
     }
-    false
+
+    valid
 }
 
-#[must_use]
-pub fn solve_part_2(red_coords: &[Coord]) -> u64 {
-    let segments = create_segments(red_coords);
-    let border_tiles = create_border_tiles(&segments);
-    let border_tiles: HashSet<Coord> = border_tiles.iter().copied().collect();
-    let mut vertical_segments = vec![];
-    for segment in segments {
-        if segment.0.x == segment.1.x {
-            vertical_segments.push(segment);
+/// Finds the largest rectangle having two red tiles as opposite corners and only
+/// red or green tiles inside.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotEnoughTiles`] when there are fewer than two red tiles
+/// and [`SolveError::Overflow`] when an area does not fit in a `u64`.
+pub fn solve_part_2(red_coords: &[Coord]) -> Result<u64, SolveError> {
+    check_coords(red_coords)?;
+    let xs = compress(red_coords.iter().map(|coord| coord.x));
+    let ys = compress(red_coords.iter().map(|coord| coord.y));
+    let valid = valid_tiles(&create_segments(red_coords), &xs, &ys);
+
+    // invalid[row][col]: invalid tiles above and left of that compressed tile.
+    let mut invalid = vec![vec![0usize; xs.len() + 1]; ys.len() + 1];
+    for (row, tiles) in valid.iter().enumerate() {
+        for (col, &tile) in tiles.iter().enumerate() {
+            invalid[row + 1][col + 1] = invalid[row][col + 1] + invalid[row + 1][col]
+                - invalid[row][col]
+                + usize::from(!tile);
         }
     }
+
     let mut result = 0;
-    let mut rectangles = create_rectangles(red_coords);
-    rectangles.sort_by_key(|rectangle| rectangle.2);
-    let mut invalid_rectangles = vec![];
-    for (point_a, point_b, area) in rectangles {
-        let rectangle = (point_a, point_b);
-        if !is_contains_invalid_rectangle(&invalid_rectangles, &rectangle) {
-            if !is_valide_rectangle(&border_tiles, &vertical_segments, &rectangle) {
-                invalid_rectangles.push(rectangle);
-            } else if result < area {
+    for (index, coord_a) in red_coords.iter().enumerate() {
+        for coord_b in &red_coords[index + 1..] {
+            let area = area(coord_a, coord_b)?;
+            let (min, max) = get_min_max(&(*coord_a, *coord_b));
+            let (top, bottom) = (position(&ys, min.y), position(&ys, max.y) + 1);
+            let (left, right) = (position(&xs, min.x), position(&xs, max.x) + 1);
+            let invalid = invalid[bottom][right] + invalid[top][left]
+                - invalid[top][right]
+                - invalid[bottom][left];
+            if invalid == 0 && result < area {
                 result = area;
             }
         }
     }
-    result
+    Ok(result)
+}
+
+pub struct Day9;
+
+impl Solution for Day9 {
+    const DAY: u8 = 9;
+
+    type Input = Vec<Coord>;
+    type Answer = u64;
+    type Error = SolveError;
+
+    fn parse(input: &str) -> Result<Self::Input, ParseError> {
+        parse_coords(input)
+    }
+
+    fn solve_part_1(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_1(input)
+    }
+
+    fn solve_part_2(input: &Self::Input) -> Result<Self::Answer, Self::Error> {
+        solve_part_2(input)
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::path::Path;
+
+    use common::{GoldenError, check_inputs};
+
     use super::*;
 
     #[test]
-    fn example_solve_part_1() {
+    fn example_solve_part_1() -> Result<(), ParseError> {
         let input = "7,1
 11,1
 11,7
@@ -234,13 +269,15 @@ mod tests {
 2,5
 2,3
 7,3";
-        let input = parse_coords(input);
+        let input = parse_coords(input)?;
         let result = solve_part_1(&input);
-        assert_eq!(result, 50);
+        assert_eq!(result, Ok(50));
+
+        Ok(())
     }
 
     #[test]
-    fn example_solve_part_2() {
+    fn example_solve_part_2() -> Result<(), ParseError> {
         let input = "7,1
 11,1
 11,7
@@ -249,8 +286,44 @@ mod tests {
 2,5
 2,3
 7,3";
-        let input = parse_coords(input);
+        let input = parse_coords(input)?;
         let result = solve_part_2(&input);
-        assert_eq!(result, 24);
+        assert_eq!(result, Ok(24));
+
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_parse_coords() {
+        let input = "7,1
+11;1
+11,7";
+        let result = parse_coords(input);
+        assert_eq!(
+            result.err(),
+            Some(ParseError::new(2, 1, "red tile position `x,y`", "11;1"))
+        );
+        let result = parse_coords_lenient(input);
+        assert_eq!(result.len(), 2);
+    }
+
+    #[test]
+    fn invalid_solve_part_1() -> Result<(), ParseError> {
+        let input = parse_coords("")?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Err(SolveError::NotEnoughTiles { found: 0 }));
+        let input = parse_coords("0,0\n18446744073709551615,1\n")?;
+        let result = solve_part_1(&input);
+        assert_eq!(result, Err(SolveError::Overflow));
+
+        Ok(())
+    }
+
+    #[test]
+    fn golden_inputs() -> Result<(), GoldenError> {
+        let inputs = Path::new(env!("CARGO_MANIFEST_DIR")).join("inputs");
+        check_inputs::<Day9>(&inputs)?;
+
+        Ok(())
     }
 }
@@ -0,0 +1,227 @@
+//! Brute force solver checking every tile of the rectangles, kept as an oracle
+//! for the fast one.
+
+use std::collections::HashSet;
+
+use rayon::prelude::*;
+
+use crate::{Coord, SolveError, area, check_coords, create_segments, get_min_max};
+
+fn is_inside(vertical_segments: &[(Coord, Coord)], point: &Coord) -> bool {
+    let mut intersects = 0;
+    for vertical_segment in vertical_segments {
+        if vertical_segment.0.x > point.x
+            && (vertical_segment.0.y > point.y) != (vertical_segment.1.y > point.y)
+        {
+            intersects += 1;
+        }
+    }
+
+    intersects % 2 == 1
+}
+
+fn create_line(segment: &(Coord, Coord)) -> Vec<Coord> {
+    let mut line = vec![];
+    if segment.0.x == segment.1.x {
+        let x = segment.0.x;
+        let y_range = if segment.0.y <= segment.1.y {
+            (segment.0.y + 1)..(segment.1.y + 1)
+        } else {
+            (segment.1.y)..segment.0.y
+        };
+        for y in y_range {
+            line.push(Coord { x, y });
+        }
+    } else if segment.0.y == segment.1.y {
+        let y = segment.0.y;
+        let x_range = if segment.0.x <= segment.1.x {
+            (segment.0.x + 1)..(segment.1.x + 1)
+        } else {
+            segment.1.x..segment.0.x
+        };
+        for x in x_range {
+            line.push(Coord { x, y });
+        }
+    }
+
+    line
+}
+
+fn create_border_tiles(segments: &[(Coord, Coord)]) -> Vec<Coord> {
+    let mut border_tiles = vec![];
+    for segment in segments {
+        let mut line = create_line(segment);
+        border_tiles.append(&mut line);
+    }
+
+    border_tiles
+}
+
+fn is_valid_tile(
+    border_tiles: &HashSet<Coord>,
+    vertical_segments: &[(Coord, Coord)],
+    tile: &Coord,
+) -> bool {
+    border_tiles.contains(tile) || is_inside(vertical_segments, tile)
+}
+
+fn is_valide_rectangle(
+    border_tiles: &HashSet<Coord>,
+    vertical_segments: &[(Coord, Coord)],
+    rectangle: &(Coord, Coord),
+) -> bool {
+    let (min, max) = get_min_max(rectangle);
+    if !is_valid_tile(
+        border_tiles,
+        vertical_segments,
+        &Coord {
+            x: rectangle.0.x,
+            y: rectangle.1.y,
+        },
+    ) || !is_valid_tile(
+        border_tiles,
+        vertical_segments,
+        &Coord {
+            x: rectangle.1.x,
+            y: rectangle.0.y,
+        },
+    ) {
+        return false;
+    }
+
+    (min.y..=max.y).into_par_iter().all(|y| {
+        (min.x..=max.x).all(|x| {
+            let tile = Coord { x, y };
+            is_valid_tile(border_tiles, vertical_segments, &tile)
+        })
+    })
+}
+
+fn create_rectangles(red_coords: &[Coord]) -> Result<Vec<(Coord, Coord, u64)>, SolveError> {
+    let mut rectangles = vec![];
+    for (index, coord_a) in red_coords.iter().enumerate().take(red_coords.len() - 1) {
+        for coord_b in red_coords.iter().skip(index + 1) {
+            let rectangle = (*coord_a, *coord_b);
+            let area = area(&rectangle.0, &rectangle.1)?;
+            let rectangle = (*coord_a, *coord_b, area);
+            rectangles.push(rectangle);
+        }
+    }
+
+    Ok(rectangles)
+}
+
+fn is_contains_invalid_rectangle(
+    invalid_rectangles: &[(Coord, Coord)],
+    rectangle: &(Coord, Coord),
+) -> bool {
+    let (min, max) = get_min_max(rectangle);
+    for invalid_rectangle in invalid_rectangles {
+        if min.x <= invalid_rectangle.0.x
+            && invalid_rectangle.0.x <= max.x
+            && min.x <= invalid_rectangle.1.x
+            && invalid_rectangle.1.x <= max.x
+            && min.y <= invalid_rectangle.0.y
+            && invalid_rectangle.0.y <= max.y
+            && min.y <= invalid_rectangle.1.y
+            && invalid_rectangle.1.y <= max.y
+        {
+            return true;
+        }
+    }
+    false
+}
+
+/// Finds the largest rectangle having two red tiles as opposite corners and only
+/// red or green tiles inside.
+///
+/// # Errors
+///
+/// Returns [`SolveError::NotEnoughTiles`] when there are fewer than two red tiles
+/// and [`SolveError::Overflow`] when an area does not fit in a `u64`.
+pub fn solve_part_2(red_coords: &[Coord]) -> Result<u64, SolveError> {
+    check_coords(red_coords)?;
+    let segments = create_segments(red_coords);
+    let border_tiles = create_border_tiles(&segments);
+    let border_tiles: HashSet<Coord> = border_tiles.iter().copied().collect();
+    let mut vertical_segments = vec![];
+    for segment in segments {
+        if segment.0.x == segment.1.x {
+            vertical_segments.push(segment);
+        }
+    }
+    let mut result = 0;
+    let mut rectangles = create_rectangles(red_coords)?;
+    rectangles.sort_by_key(|rectangle| rectangle.2);
+    let mut invalid_rectangles = vec![];
+    for (point_a, point_b, area) in rectangles {
+        let rectangle = (point_a, point_b);
+        if !is_contains_invalid_rectangle(&invalid_rectangles, &rectangle) {
+            if !is_valide_rectangle(&border_tiles, &vertical_segments, &rectangle) {
+                invalid_rectangles.push(rectangle);
+            } else if result < area {
+                result = area;
+            }
+        }
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use common::{Counterexample, differential};
+
+    use super::*;
+    use crate::generate::{Options, generate};
+    use crate::parse_coords;
+
+    type Case = Counterexample<Coord, Result<u64, SolveError>>;
+
+    /// The same red tile closer to the origin. Both solvers only consider the
+    /// axis-aligned segments, so the loop does not need to stay well formed.
+    fn simplify(coord: &Coord) -> Vec<Coord> {
+        let mut simpler = vec![];
+        if coord.x > 0 {
+            simpler.push(Coord {
+                x: coord.x / 2,
+                y: coord.y,
+            });
+        }
+        if coord.y > 0 {
+            simpler.push(Coord {
+                x: coord.x,
+                y: coord.y / 2,
+            });
+        }
+        simpler
+    }
+
+    fn check(options: &Options) -> Result<(), Case> {
+        differential(
+            0..50,
+            |seed| {
+                parse_coords(&generate(seed, options))
+                    .unwrap_or_else(|err| panic!("seed {seed} generated an invalid input: {err}"))
+            },
+            simplify,
+            solve_part_2,
+            crate::solve_part_2,
+        )
+    }
+
+    #[test]
+    fn fast_matches_reference() -> Result<(), Case> {
+        check(&Options {
+            columns: 3,
+            max_width: 4,
+            height: 8,
+        })?;
+        check(&Options {
+            columns: 8,
+            max_width: 10,
+            height: 30,
+        })?;
+
+        Ok(())
+    }
+}